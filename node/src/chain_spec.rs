@@ -160,7 +160,7 @@ fn testnet_genesis(
             // Assign network admin rights.
             key: root_key,
         },
-        // kitties: Default::default(),
+        kitties: Default::default(),
         nft: Default::default(),
     }
 }
@@ -0,0 +1,44 @@
+//! Outcome of a battle between two kitties.
+//!
+//! Every kitty has a battle power derived from its phenotype: each visible trait adds its
+//! expressed allele plus one, and every accessory it wears adds [`ACCESSORY_BONUS`]. A battle is
+//! decided by a single roll, the challenger wins with a probability of its share of the combined
+//! power of both kitties. The outcome only depends on the two DNAs and the random value, so the
+//! same inputs always give the same winner.
+
+use super::*;
+use genetics::Gene;
+use phenotype::{BODY_COLOUR_GENE, FUR_GENE};
+
+/// Power added by every accessory a kitty wears.
+pub const ACCESSORY_BONUS: u32 = 4;
+
+/// The side that won a battle.
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone, Copy)]
+pub enum Winner {
+    Challenger,
+    Opponent,
+}
+
+/// Battle power of a kitty, never zero.
+pub fn power(kitty: &Kitty) -> u32 {
+    let traits: u32 = (BODY_COLOUR_GENE..=FUR_GENE)
+        .map(|gene| u32::from(Gene(kitty.0[gene]).expressed()) + 1)
+        .sum();
+    let accessories = kitty.phenotype().accessories.iter().flatten().count() as u32;
+
+    traits + accessories * ACCESSORY_BONUS
+}
+
+/// Decide a battle between two kitties with a fresh random value.
+pub fn resolve_battle(challenger: &Kitty, opponent: &Kitty, random: &[u8; 16]) -> Winner {
+    let challenger_power = power(challenger);
+    let total = challenger_power + power(opponent);
+
+    let roll = u32::from_le_bytes([random[0], random[1], random[2], random[3]]) % total;
+    if roll < challenger_power {
+        Winner::Challenger
+    } else {
+        Winner::Opponent
+    }
+}
@@ -3,49 +3,672 @@ use super::*;
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
 use frame_system::RawOrigin;
 
+/// Mint a kitty into the genesis collection
+fn mint<T: Config>(owner: &T::AccountId, kitty: Kitty) -> Result<KittyIdOf<T>, DispatchError> {
+    let class_id = Pallet::<T>::class_id();
+    let token_id = orml_nft::Pallet::<T>::mint(owner, class_id, Vec::new(), kitty)?;
+    Ok((class_id, token_id))
+}
+
+/// Give an account enough funds for all the deposits it may need
+fn fund<T: Config>(who: &T::AccountId) {
+    let amount = T::KittyDeposit::get()
+        .saturating_add(T::ListingDeposit::get())
+        .saturating_mul(10u32.into())
+        .saturating_add(10_000u32.into());
+    let _ = T::Currency::make_free_balance_be(who, amount);
+}
+
+/// Reserve the kitty deposit from the owner, like `create_kitty` does
+fn reserve_deposit<T: Config>(owner: &T::AccountId, kitty_id: KittyIdOf<T>) -> DispatchResult {
+    fund::<T>(owner);
+    T::Currency::reserve(owner, T::KittyDeposit::get())?;
+    KittyDeposits::<T>::insert(kitty_id, T::KittyDeposit::get());
+    Ok(())
+}
+
+/// Give a kitty a creator other than the trading accounts, taking the highest royalty
+fn set_creator<T: Config>(kitty_id: KittyIdOf<T>) {
+    let creator: T::AccountId = account("creator", 0, 0);
+    let _ = T::Currency::make_free_balance_be(&creator, 1000u32.into());
+    Royalties::<T>::insert(kitty_id, Royalty { creator, rate: T::MaxRoyalty::get() });
+}
+
+/// The smallest valid offer, standing as long as an offer can
+fn offer<T: Config>() -> (BalanceOf<T>, T::BlockNumber) {
+    let amount = T::Currency::minimum_balance().max(100u32.into());
+    let expiry = frame_system::Pallet::<T>::block_number() + T::MaxOfferDuration::get();
+    (amount, expiry)
+}
+
+/// Put `n` standing offers from different buyers on a kitty
+fn make_offers<T: Config>(kitty_id: KittyIdOf<T>, n: u32) -> DispatchResult {
+    for i in 0..n {
+        let buyer: T::AccountId = account("buyer", i, 0);
+        fund::<T>(&buyer);
+        let (amount, expiry) = offer::<T>();
+        Pallet::<T>::make_offer(RawOrigin::Signed(buyer).into(), kitty_id, amount, expiry)?;
+    }
+    Ok(())
+}
+
+/// The smallest valid challenge stake
+fn challenge_stake<T: Config>() -> BalanceOf<T> {
+    T::MinChallengeStake::get().max(100u32.into())
+}
+
+/// Have a kitty challenge another one and put `n` challenges from different challengers against it
+fn make_challenges<T: Config>(kitty_id: KittyIdOf<T>, n: u32) -> DispatchResult {
+    let stake = challenge_stake::<T>();
+    let rival: T::AccountId = account("rival", 0, 0);
+    let rival_id = mint::<T>(&rival, Kitty(Default::default()))?;
+    let owner = orml_nft::Pallet::<T>::tokens(kitty_id.0, kitty_id.1)
+        .ok_or(Error::<T>::InvalidKittyId)?
+        .owner;
+    Pallet::<T>::challenge(RawOrigin::Signed(owner).into(), kitty_id, rival_id, stake)?;
+    for i in 0..n {
+        let challenger: T::AccountId = account("challenger", i, 0);
+        fund::<T>(&challenger);
+        let challenger_id = mint::<T>(&challenger, Kitty(Default::default()))?;
+        Pallet::<T>::challenge(RawOrigin::Signed(challenger).into(), challenger_id, kitty_id, stake)?;
+    }
+    Ok(())
+}
+
 benchmarks! {
-    create {
+    create_kitty {
         let caller = whitelisted_caller();
-    }: _(RawOrigin::Signed(caller))
+        fund::<T>(&caller);
+    }: _(RawOrigin::Signed(caller), Pallet::<T>::class_id())
 
-    breed {
+    breed_kitties {
         let caller = whitelisted_caller();
+        fund::<T>(&caller);
 
         // some setup, we need kitties
         let mut kitty = Kitty(Default::default());
-        let kitty_id = orml_nft::Pallet::<T>::mint(&caller, Pallet::<T>::class_id(), Vec::new(), kitty.clone())?;
+        let kitty_id = mint::<T>(&caller, kitty.clone())?;
 
         kitty.0[0] = 1;
-        let kitty_id2 = orml_nft::Pallet::<T>::mint(&caller, Pallet::<T>::class_id(), Vec::new(), kitty)?;
+        let kitty_id2 = mint::<T>(&caller, kitty)?;
 
     }: _(RawOrigin::Signed(caller), kitty_id, kitty_id2)
 
     transfer {
+        let o in 0 .. T::MaxOffers::get();
+        let c in 0 .. T::MaxChallenges::get();
+
         let caller = whitelisted_caller();
         // transfer a kitty to a test account
         let to = account("to", 0, 0);
+        fund::<T>(&to);
 
-        let kitty_id = orml_nft::Pallet::<T>::mint(&caller, Pallet::<T>::class_id(), Vec::new(), Kitty(Default::default()))?;
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        reserve_deposit::<T>(&caller, kitty_id)?;
+        Pallet::<T>::set_price(RawOrigin::Signed(caller.clone()).into(), kitty_id, Some(500u32.into()))?;
+        make_offers::<T>(kitty_id, o)?;
+        make_challenges::<T>(kitty_id, c)?;
 
     }: _(RawOrigin::Signed(caller), to, kitty_id)
 
     set_price {
         let caller = whitelisted_caller();
+        fund::<T>(&caller);
 
-        let kitty_id = orml_nft::Pallet::<T>::mint(&caller, Pallet::<T>::class_id(), Vec::new(), Kitty(Default::default()))?;
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
 
     }: _(RawOrigin::Signed(caller), kitty_id, Some(100u32.into()))
 
     buy {
+        let o in 0 .. T::MaxOffers::get();
+
         let caller = whitelisted_caller();
         let seller = account("seller", 0, 0);
 
         let _ = T::Currency::make_free_balance_be(&caller, 1000u32.into());
 
-        let kitty_id = orml_nft::Pallet::<T>::mint(&seller, Pallet::<T>::class_id(), Vec::new(), Kitty(Default::default()))?;
+        let kitty_id = mint::<T>(&seller, Kitty(Default::default()))?;
+        reserve_deposit::<T>(&seller, kitty_id)?;
         Pallet::<T>::set_price(RawOrigin::Signed(seller.clone()).into(), kitty_id, Some(500u32.into()))?;
+        make_offers::<T>(kitty_id, o)?;
+        set_creator::<T>(kitty_id);
 
     }: _(RawOrigin::Signed(caller), seller, kitty_id, 500u32.into())
+
+    set_sire_price {
+        let caller = whitelisted_caller();
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id, Some(100u32.into()))
+
+    breed_with_sire {
+        let caller = whitelisted_caller();
+        let sire_owner = account("sire_owner", 0, 0);
+
+        fund::<T>(&caller);
+
+        let mut kitty = Kitty(Default::default());
+        kitty.0[0] = 1;
+        let kitty_id = mint::<T>(&caller, kitty)?;
+
+        let sire_id = mint::<T>(&sire_owner, Kitty(Default::default()))?;
+        Pallet::<T>::set_sire_price(RawOrigin::Signed(sire_owner).into(), sire_id, Some(500u32.into()))?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id, sire_id, 500u32.into())
+
+    create_auction {
+        let caller = whitelisted_caller();
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        let end = frame_system::Pallet::<T>::block_number() + T::MaxAuctionDuration::get();
+
+    }: _(RawOrigin::Signed(caller), kitty_id, 100u32.into(), end)
+
+    bid {
+        let caller = whitelisted_caller();
+        let seller = account("seller", 0, 0);
+        let outbid: T::AccountId = account("outbid", 0, 0);
+
+        let _ = T::Currency::make_free_balance_be(&caller, 1000u32.into());
+        let _ = T::Currency::make_free_balance_be(&outbid, 1000u32.into());
+
+        let kitty_id = mint::<T>(&seller, Kitty(Default::default()))?;
+        // the bid lands in the extension period
+        let end = frame_system::Pallet::<T>::block_number() + 1u32.into();
+        Pallet::<T>::create_auction(RawOrigin::Signed(seller).into(), kitty_id, 100u32.into(), end)?;
+        Pallet::<T>::bid(RawOrigin::Signed(outbid).into(), kitty_id, 400u32.into())?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id, 500u32.into())
+
+    cancel_auction {
+        let caller = whitelisted_caller();
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        let end = frame_system::Pallet::<T>::block_number() + T::MaxAuctionDuration::get();
+        Pallet::<T>::create_auction(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into(), end)?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id)
+
+    create_dutch_auction {
+        let caller = whitelisted_caller();
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id, 1000u32.into(), 100u32.into(), 100u32.into())
+
+    buy_dutch {
+        let o in 0 .. T::MaxOffers::get();
+
+        let caller = whitelisted_caller();
+        let seller = account("seller", 0, 0);
+
+        let _ = T::Currency::make_free_balance_be(&caller, 2000u32.into());
+
+        let kitty_id = mint::<T>(&seller, Kitty(Default::default()))?;
+        reserve_deposit::<T>(&seller, kitty_id)?;
+        Pallet::<T>::create_dutch_auction(RawOrigin::Signed(seller).into(), kitty_id, 1000u32.into(), 100u32.into(), 100u32.into())?;
+        make_offers::<T>(kitty_id, o)?;
+        set_creator::<T>(kitty_id);
+
+    }: _(RawOrigin::Signed(caller), kitty_id, 1000u32.into())
+
+    cancel_dutch_auction {
+        let caller = whitelisted_caller();
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        Pallet::<T>::create_dutch_auction(RawOrigin::Signed(caller.clone()).into(), kitty_id, 1000u32.into(), 100u32.into(), 100u32.into())?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id)
+
+    make_offer {
+        let o in 1 .. T::MaxOffers::get();
+
+        let caller = whitelisted_caller();
+        let owner = account("owner", 0, 0);
+
+        fund::<T>(&caller);
+
+        let kitty_id = mint::<T>(&owner, Kitty(Default::default()))?;
+        // refunding the expired offers is the expensive part
+        make_offers::<T>(kitty_id, o)?;
+        let (_, expired) = offer::<T>();
+        frame_system::Pallet::<T>::set_block_number(expired);
+        let (amount, expiry) = offer::<T>();
+
+    }: _(RawOrigin::Signed(caller), kitty_id, amount, expiry)
+    verify {
+        assert_eq!(Offers::<T>::iter_prefix(kitty_id).count(), 1);
+    }
+
+    withdraw_offer {
+        let caller = whitelisted_caller();
+        let owner = account("owner", 0, 0);
+
+        fund::<T>(&caller);
+
+        let kitty_id = mint::<T>(&owner, Kitty(Default::default()))?;
+        let (amount, expiry) = offer::<T>();
+        Pallet::<T>::make_offer(RawOrigin::Signed(caller.clone()).into(), kitty_id, amount, expiry)?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id)
+
+    accept_offer {
+        let o in 1 .. T::MaxOffers::get();
+
+        let caller = whitelisted_caller();
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        reserve_deposit::<T>(&caller, kitty_id)?;
+        make_offers::<T>(kitty_id, o)?;
+        set_creator::<T>(kitty_id);
+        let buyer: T::AccountId = account("buyer", 0, 0);
+
+    }: _(RawOrigin::Signed(caller), kitty_id, buyer, 100u32.into())
+
+    set_royalty {
+        let caller: T::AccountId = whitelisted_caller();
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        Royalties::<T>::insert(kitty_id, Royalty { creator: caller.clone(), rate: Perbill::zero() });
+
+    }: _(RawOrigin::Signed(caller), kitty_id, T::MaxRoyalty::get())
+
+    release_kitty {
+        let o in 0 .. T::MaxOffers::get();
+        let c in 0 .. T::MaxChallenges::get();
+
+        let caller = whitelisted_caller();
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        reserve_deposit::<T>(&caller, kitty_id)?;
+        Pallet::<T>::set_price(RawOrigin::Signed(caller.clone()).into(), kitty_id, Some(500u32.into()))?;
+        make_offers::<T>(kitty_id, o)?;
+        make_challenges::<T>(kitty_id, c)?;
+
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id)
+    verify {
+        assert!(orml_nft::Pallet::<T>::tokens(kitty_id.0, kitty_id.1).is_none());
+        assert_eq!(T::Currency::reserved_balance(&caller), 0u32.into());
+    }
+
+    set_kitty_name {
+        let caller = whitelisted_caller();
+        fund::<T>(&caller);
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        let name = sp_std::vec![b'a'; T::MaxNameLength::get() as usize];
+
+    }: _(RawOrigin::Signed(caller), kitty_id, name)
+    verify {
+        assert!(KittyNames::<T>::contains_key(kitty_id));
+    }
+
+    set_kitty_uri {
+        let caller = whitelisted_caller();
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        let uri = sp_std::vec![b'a'; <T as orml_nft::Config>::MaxTokenMetadata::get() as usize];
+
+    }: _(RawOrigin::Signed(caller), kitty_id, uri)
+
+    set_marketplace_fee {
+    }: _(RawOrigin::Root, Perbill::from_percent(5))
+    verify {
+        assert_eq!(Pallet::<T>::marketplace_fee(), Perbill::from_percent(5));
+    }
+
+    create_collection {
+        let origin = T::CollectionOrigin::successful_origin();
+        let metadata = sp_std::vec![b'a'; <T as orml_nft::Config>::MaxClassMetadata::get() as usize];
+
+    }: _<T::Origin>(origin, metadata, Some(1000), 0u32.into(), None)
+
+    approve {
+        let caller = whitelisted_caller();
+        let spender: T::AccountId = account("spender", 0, 0);
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id, Some(spender.clone()))
+    verify {
+        assert_eq!(Pallet::<T>::approvals(kitty_id), Some(spender));
+    }
+
+    set_approval_for_all {
+        let caller: T::AccountId = whitelisted_caller();
+        let operator: T::AccountId = account("operator", 0, 0);
+
+    }: _(RawOrigin::Signed(caller.clone()), operator.clone(), true)
+    verify {
+        assert!(Operators::<T>::contains_key(&caller, &operator));
+    }
+
+    transfer_from {
+        let o in 0 .. T::MaxOffers::get();
+
+        let caller: T::AccountId = whitelisted_caller();
+        let owner: T::AccountId = account("owner", 0, 0);
+        let to: T::AccountId = account("to", 0, 0);
+        fund::<T>(&to);
+
+        let kitty_id = mint::<T>(&owner, Kitty(Default::default()))?;
+        reserve_deposit::<T>(&owner, kitty_id)?;
+        Pallet::<T>::set_price(RawOrigin::Signed(owner.clone()).into(), kitty_id, Some(500u32.into()))?;
+        make_offers::<T>(kitty_id, o)?;
+        // the operator check is the last one
+        Pallet::<T>::set_approval_for_all(RawOrigin::Signed(owner.clone()).into(), caller.clone(), true)?;
+
+    }: _(RawOrigin::Signed(caller), owner, to.clone(), kitty_id)
+    verify {
+        assert!(orml_nft::TokensByOwner::<T>::contains_key(&to, kitty_id));
+    }
+
+    batch_transfer {
+        let n in 1 .. T::MaxBatchSize::get();
+
+        let caller = whitelisted_caller();
+        let to: T::AccountId = account("to", 0, 0);
+        fund::<T>(&to);
+
+        let mut transfers = Vec::new();
+        for _ in 0 .. n {
+            let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+            reserve_deposit::<T>(&caller, kitty_id)?;
+            let origin = RawOrigin::Signed(caller.clone()).into();
+            Pallet::<T>::set_price(origin, kitty_id, Some(500u32.into()))?;
+            make_offers::<T>(kitty_id, T::MaxOffers::get())?;
+            transfers.push((to.clone(), kitty_id));
+        }
+
+    }: _(RawOrigin::Signed(caller), transfers)
+    verify {
+        assert_eq!(Pallet::<T>::kitties_owned(&to), n);
+    }
+
+    batch_set_price {
+        let n in 1 .. T::MaxBatchSize::get();
+
+        let caller = whitelisted_caller();
+        fund::<T>(&caller);
+
+        let mut prices = Vec::new();
+        for _ in 0 .. n {
+            let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+            prices.push((kitty_id, Some(100u32.into())));
+        }
+
+    }: _(RawOrigin::Signed(caller), prices)
+
+    offer_transfer {
+        let caller = whitelisted_caller();
+        let to: T::AccountId = account("to", 0, 0);
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+
+    }: _(RawOrigin::Signed(caller), to, kitty_id)
+    verify {
+        assert!(PendingTransfers::<T>::contains_key(kitty_id));
+    }
+
+    accept_transfer {
+        let o in 0 .. T::MaxOffers::get();
+
+        let caller: T::AccountId = whitelisted_caller();
+        let from: T::AccountId = account("from", 0, 0);
+        fund::<T>(&caller);
+
+        let kitty_id = mint::<T>(&from, Kitty(Default::default()))?;
+        reserve_deposit::<T>(&from, kitty_id)?;
+        Pallet::<T>::set_price(RawOrigin::Signed(from.clone()).into(), kitty_id, Some(500u32.into()))?;
+        make_offers::<T>(kitty_id, o)?;
+        Pallet::<T>::offer_transfer(RawOrigin::Signed(from).into(), caller.clone(), kitty_id)?;
+
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id)
+    verify {
+        assert!(orml_nft::TokensByOwner::<T>::contains_key(&caller, kitty_id));
+    }
+
+    reject_transfer {
+        let caller: T::AccountId = whitelisted_caller();
+        let from: T::AccountId = account("from", 0, 0);
+
+        let kitty_id = mint::<T>(&from, Kitty(Default::default()))?;
+        Pallet::<T>::offer_transfer(RawOrigin::Signed(from).into(), caller.clone(), kitty_id)?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id)
+
+    cancel_transfer {
+        let caller: T::AccountId = whitelisted_caller();
+        let to: T::AccountId = account("to", 0, 0);
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        Pallet::<T>::offer_transfer(RawOrigin::Signed(caller.clone()).into(), to, kitty_id)?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id)
+
+    set_rental_terms {
+        let caller = whitelisted_caller();
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        let terms = RentalTerms { price_per_block: 1u32.into(), max_duration: 100u32.into() };
+
+    }: _(RawOrigin::Signed(caller), kitty_id, Some(terms))
+
+    rent {
+        let caller: T::AccountId = whitelisted_caller();
+        let owner: T::AccountId = account("owner", 0, 0);
+        fund::<T>(&caller);
+
+        let kitty_id = mint::<T>(&owner, Kitty(Default::default()))?;
+        let terms = RentalTerms { price_per_block: 1u32.into(), max_duration: 100u32.into() };
+        Pallet::<T>::set_rental_terms(RawOrigin::Signed(owner.clone()).into(), kitty_id, Some(terms))?;
+        Pallet::<T>::set_sire_price(RawOrigin::Signed(owner).into(), kitty_id, Some(100u32.into()))?;
+
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, 100u32.into(), 100u32.into())
+    verify {
+        assert_eq!(Pallet::<T>::rentals(kitty_id).map(|rental| rental.renter), Some(caller));
+    }
+
+    fractionalize {
+        let o in 0 .. T::MaxOffers::get();
+
+        let caller = whitelisted_caller();
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        reserve_deposit::<T>(&caller, kitty_id)?;
+        Pallet::<T>::set_price(RawOrigin::Signed(caller.clone()).into(), kitty_id, Some(500u32.into()))?;
+        make_offers::<T>(kitty_id, o)?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id, 100, 1000u32.into())
+    verify {
+        assert_eq!(Pallet::<T>::kitty_vaults(kitty_id), Some(0));
+    }
+
+    transfer_shares {
+        let caller: T::AccountId = whitelisted_caller();
+        let to: T::AccountId = account("to", 0, 0);
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        Pallet::<T>::fractionalize(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100, 1000u32.into())?;
+
+    }: _(RawOrigin::Signed(caller), 0, to.clone(), 50)
+    verify {
+        assert_eq!(Pallet::<T>::shares(0, &to), 50);
+    }
+
+    redeem {
+        let caller: T::AccountId = whitelisted_caller();
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        reserve_deposit::<T>(&caller, kitty_id)?;
+        Pallet::<T>::fractionalize(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100, 1000u32.into())?;
+
+    }: _(RawOrigin::Signed(caller.clone()), 0)
+    verify {
+        assert!(orml_nft::TokensByOwner::<T>::contains_key(&caller, kitty_id));
+    }
+
+    buyout {
+        let caller: T::AccountId = whitelisted_caller();
+        let owner: T::AccountId = account("owner", 0, 0);
+        fund::<T>(&caller);
+
+        let kitty_id = mint::<T>(&owner, Kitty(Default::default()))?;
+        reserve_deposit::<T>(&owner, kitty_id)?;
+        Pallet::<T>::fractionalize(RawOrigin::Signed(owner.clone()).into(), kitty_id, 100, 1000u32.into())?;
+        set_creator::<T>(kitty_id);
+        // the buyer holds some shares, so the vault stays open for the claims
+        Pallet::<T>::transfer_shares(RawOrigin::Signed(owner).into(), 0, caller.clone(), 1)?;
+
+    }: _(RawOrigin::Signed(caller.clone()), 0, 1000u32.into())
+    verify {
+        assert!(orml_nft::TokensByOwner::<T>::contains_key(&caller, kitty_id));
+    }
+
+    claim_buyout {
+        let caller: T::AccountId = whitelisted_caller();
+        let buyer: T::AccountId = account("buyer", 0, 0);
+        fund::<T>(&buyer);
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        Pallet::<T>::fractionalize(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100, 1000u32.into())?;
+        Pallet::<T>::buyout(RawOrigin::Signed(buyer).into(), 0, 1000u32.into())?;
+
+    }: _(RawOrigin::Signed(caller), 0)
+    verify {
+        assert_eq!(Pallet::<T>::vaults(0), None);
+    }
+
+    stake_kitty {
+        let caller = whitelisted_caller();
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id)
+    verify {
+        assert!(StakedKitties::<T>::contains_key(kitty_id));
+    }
+
+    // the cost doesn't depend on how long the kitty was staked
+    unstake_kitty {
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        Pallet::<T>::fund_rewards(RawOrigin::Signed(caller.clone()).into(), 1000u32.into())?;
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        Pallet::<T>::stake_kitty(RawOrigin::Signed(caller.clone()).into(), kitty_id)?;
+        frame_system::Pallet::<T>::set_block_number(100u32.into());
+
+    }: _(RawOrigin::Signed(caller), kitty_id)
+    verify {
+        assert!(Pallet::<T>::kitty_xp(kitty_id) > 0);
+    }
+
+    claim_rewards {
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        Pallet::<T>::fund_rewards(RawOrigin::Signed(caller.clone()).into(), 1000u32.into())?;
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        Pallet::<T>::stake_kitty(RawOrigin::Signed(caller.clone()).into(), kitty_id)?;
+        frame_system::Pallet::<T>::set_block_number(100u32.into());
+
+    }: _(RawOrigin::Signed(caller), kitty_id)
+    verify {
+        assert!(Pallet::<T>::kitty_xp(kitty_id) > 0);
+    }
+
+    fund_rewards {
+        let caller = whitelisted_caller();
+        fund::<T>(&caller);
+
+    }: _(RawOrigin::Signed(caller), 1000u32.into())
+
+    challenge {
+        let caller: T::AccountId = whitelisted_caller();
+        let owner: T::AccountId = account("owner", 0, 0);
+        fund::<T>(&caller);
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        let opponent_id = mint::<T>(&owner, Kitty(Default::default()))?;
+        // counting the challenges against the opponent is the expensive part
+        for i in 1 .. T::MaxChallenges::get() {
+            let challenger: T::AccountId = account("challenger", i, 0);
+            fund::<T>(&challenger);
+            let challenger_id = mint::<T>(&challenger, Kitty(Default::default()))?;
+            Pallet::<T>::challenge(RawOrigin::Signed(challenger).into(), challenger_id, opponent_id, challenge_stake::<T>())?;
+        }
+
+    }: _(RawOrigin::Signed(caller), kitty_id, opponent_id, challenge_stake::<T>())
+    verify {
+        assert!(Challenges::<T>::contains_key(kitty_id));
+    }
+
+    cancel_challenge {
+        let caller: T::AccountId = whitelisted_caller();
+        let owner: T::AccountId = account("owner", 0, 0);
+        fund::<T>(&caller);
+
+        let kitty_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        let opponent_id = mint::<T>(&owner, Kitty(Default::default()))?;
+        Pallet::<T>::challenge(RawOrigin::Signed(caller.clone()).into(), kitty_id, opponent_id, challenge_stake::<T>())?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id)
+
+    reject_challenge {
+        let caller: T::AccountId = whitelisted_caller();
+        let challenger: T::AccountId = account("challenger", 0, 0);
+        fund::<T>(&challenger);
+
+        let kitty_id = mint::<T>(&challenger, Kitty(Default::default()))?;
+        let opponent_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        Pallet::<T>::challenge(RawOrigin::Signed(challenger.clone()).into(), kitty_id, opponent_id, challenge_stake::<T>())?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id)
+    verify {
+        assert!(!Challenges::<T>::contains_key(kitty_id));
+        assert_eq!(T::Currency::reserved_balance(&challenger), 0u32.into());
+    }
+
+    accept_challenge {
+        let caller: T::AccountId = whitelisted_caller();
+        let challenger: T::AccountId = account("challenger", 0, 0);
+        fund::<T>(&caller);
+        fund::<T>(&challenger);
+
+        let kitty_id = mint::<T>(&challenger, Kitty(Default::default()))?;
+        let opponent_id = mint::<T>(&caller, Kitty(Default::default()))?;
+        Pallet::<T>::challenge(RawOrigin::Signed(challenger).into(), kitty_id, opponent_id, challenge_stake::<T>())?;
+
+    }: _(RawOrigin::Signed(caller), kitty_id)
+    verify {
+        let fight_at = frame_system::Pallet::<T>::block_number() + One::one();
+        assert!(Pallet::<T>::pending_battles(fight_at, kitty_id).is_some());
+    }
+
+    on_initialize {
+        let n in 0 .. T::MaxScheduledPerBlock::get();
+
+        let seller: T::AccountId = account("seller", 0, 0);
+        let end = frame_system::Pallet::<T>::block_number() + T::MaxAuctionDuration::get();
+
+        for i in 0 .. n {
+            let bidder: T::AccountId = account("bidder", i, 0);
+            let _ = T::Currency::make_free_balance_be(&bidder, 1000u32.into());
+
+            let kitty_id = mint::<T>(&seller, Kitty(Default::default()))?;
+            reserve_deposit::<T>(&seller, kitty_id)?;
+            Pallet::<T>::create_auction(RawOrigin::Signed(seller.clone()).into(), kitty_id, 100u32.into(), end)?;
+            make_offers::<T>(kitty_id, T::MaxOffers::get())?;
+            set_creator::<T>(kitty_id);
+            Pallet::<T>::bid(RawOrigin::Signed(bidder).into(), kitty_id, 500u32.into())?;
+        }
+
+    }: {
+        Pallet::<T>::on_initialize(end);
+    }
+    verify {
+        assert_eq!(AuctionEndTime::<T>::iter_prefix(end).count(), 0);
+    }
 }
 
 impl_benchmark_test_suite!(Pallet, crate::tests::new_test_ext(), crate::tests::Test,);
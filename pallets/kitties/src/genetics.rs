@@ -0,0 +1,116 @@
+//! Breeding algorithms combining the DNA of two parents into the DNA of a child.
+//!
+//! In the Mendelian model every byte of the DNA is a gene made of two 4 bit alleles. The allele
+//! with the lower value is dominant and is the one expressed in the [`Phenotype`]; the other one
+//! is recessive and stays hidden, but can still be passed on to the offspring.
+
+use super::*;
+use sp_runtime::{DispatchError, Perbill};
+use sp_std::marker::PhantomData;
+
+/// The algorithm used by `breed_kitties` to combine the parents into a child.
+pub trait Breeder {
+    /// Breed a child out of two parents, `random` is a fresh random value for this breeding.
+    fn breed(parent1: &Kitty, parent2: &Kitty, random: &[u8; 16]) -> Result<Kitty, DispatchError>;
+}
+
+/// Bitwise mixing of the parents DNA, see [`legacy`].
+pub struct LegacyBreeder;
+
+impl Breeder for LegacyBreeder {
+    fn breed(parent1: &Kitty, parent2: &Kitty, random: &[u8; 16]) -> Result<Kitty, DispatchError> {
+        Ok(Kitty(legacy(&parent1.0, &parent2.0, random)))
+    }
+}
+
+/// The default breeder is the legacy bitwise mixer.
+impl Breeder for () {
+    fn breed(parent1: &Kitty, parent2: &Kitty, random: &[u8; 16]) -> Result<Kitty, DispatchError> {
+        LegacyBreeder::breed(parent1, parent2, random)
+    }
+}
+
+/// Dominant / recessive alleles, every gene mutating with probability `P`, see [`mendelian`].
+pub struct MendelianBreeder<P>(PhantomData<P>);
+
+impl<P: Get<Perbill>> Breeder for MendelianBreeder<P> {
+    fn breed(parent1: &Kitty, parent2: &Kitty, random: &[u8; 16]) -> Result<Kitty, DispatchError> {
+        Ok(Kitty(mendelian(&parent1.0, &parent2.0, random, P::get())))
+    }
+}
+
+/// A single gene holding two alleles.
+#[derive(RuntimeDebug, Eq, PartialEq, Clone, Copy)]
+pub struct Gene(pub u8);
+
+impl Gene {
+    pub fn new(first: u8, second: u8) -> Self {
+        Gene(((first & 0x0f) << 4) | (second & 0x0f))
+    }
+
+    pub fn alleles(&self) -> [u8; 2] {
+        [self.0 >> 4, self.0 & 0x0f]
+    }
+
+    /// The dominant allele, i.e. the one that shows
+    pub fn expressed(&self) -> u8 {
+        let [first, second] = self.alleles();
+        first.min(second)
+    }
+
+    /// The recessive allele, hidden unless both alleles are the same
+    pub fn recessive(&self) -> u8 {
+        let [first, second] = self.alleles();
+        first.max(second)
+    }
+}
+
+pub fn combine_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
+    // selector[bit_index] == 0 -> use dna1[bit_index]
+    // selector[bit_index] == 1 -> use dna2[bit_index]
+    // e.g.
+    // selector     = 0b00000001
+    // dna1		= 0b10101010
+    // dna2		= 0b00001111
+    // result	= 0b10101011
+
+    (!selector & dna1) | (selector & dna2)
+}
+
+/// Legacy algorithm: every bit of the child is taken from one of the parents
+pub fn legacy(dna1: &[u8; 16], dna2: &[u8; 16], selector: &[u8; 16]) -> [u8; 16] {
+    let mut new_dna = [0u8; 16];
+    // Combine parents and selector to create new kitty
+    for (i, gene) in new_dna.iter_mut().enumerate() {
+        *gene = combine_dna(dna1[i], dna2[i], selector[i]);
+    }
+    new_dna
+}
+
+/// Mendelian algorithm: for every gene the child inherits one allele from each parent, picked at
+/// random, and each gene then mutates with the given probability.
+pub fn mendelian(
+    dna1: &[u8; 16],
+    dna2: &[u8; 16],
+    seed: &[u8; 16],
+    mutation_probability: Perbill,
+) -> [u8; 16] {
+    let mut new_dna = [0u8; 16];
+    for (i, gene) in new_dna.iter_mut().enumerate() {
+        // every gene gets its own entropy derived from the seed
+        let entropy = (seed, i as u8).using_encoded(blake2_128);
+
+        let from_parent1 = Gene(dna1[i]).alleles()[(entropy[0] & 1) as usize];
+        let from_parent2 = Gene(dna2[i]).alleles()[((entropy[0] >> 1) & 1) as usize];
+        let mut alleles = [from_parent1, from_parent2];
+
+        let roll = u32::from_le_bytes([entropy[1], entropy[2], entropy[3], entropy[4]]);
+        if Perbill::from_parts(roll % 1_000_000_000) < mutation_probability {
+            // replace one of the alleles with a random one
+            alleles[(entropy[5] & 1) as usize] = entropy[6] & 0x0f;
+        }
+
+        *gene = Gene::new(alleles[0], alleles[1]).0;
+    }
+    new_dna
+}
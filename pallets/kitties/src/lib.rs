@@ -1,13 +1,23 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use frame_support::pallet_prelude::*;
-use frame_support::traits::{Currency, ExistenceRequirement, Randomness};
-use frame_support::transactional;
-use frame_system::{ensure_signed, pallet_prelude::OriginFor};
+use frame_support::traits::{
+    BalanceStatus, Currency, ExistenceRequirement, OnUnbalanced, Randomness, ReservableCurrency,
+    WithdrawReasons,
+};
+use frame_support::{transactional, BoundedVec, PalletId};
+use frame_system::{
+    ensure_root, ensure_signed,
+    pallet_prelude::{BlockNumberFor, OriginFor},
+};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use sp_io::hashing::blake2_128;
-use sp_std::prelude::*;
+use sp_runtime::{
+    traits::{AccountIdConversion, One, Saturating, Zero},
+    ArithmeticError, Perbill, SaturatedConversion, TokenError,
+};
+use sp_std::{collections::btree_set::BTreeSet, convert::TryInto, prelude::*};
 
 pub use pallet::*;
 
@@ -18,10 +28,192 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod battle;
+pub mod genetics;
+pub mod migrations;
+pub mod phenotype;
+pub mod weights;
+pub use genetics::Breeder;
+pub use phenotype::Phenotype;
+pub use weights::WeightInfo;
+
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
 pub struct Kitty(pub [u8; 16]);
 
+/// Breeding history of a kitty
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone, Default)]
+pub struct BreedingState<BlockNumber> {
+    /// How many times the kitty has been bred
+    pub breed_count: u32,
+    /// The first block the kitty can breed again
+    pub ready_at: BlockNumber,
+}
+
+/// Pedigree of a kitty
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct KittyInfo<KittyIndex, BlockNumber> {
+    /// 0 for created kitties, one more than the highest parent generation for bred ones
+    pub generation: u32,
+    /// The parents of a bred kitty, None for created kitties
+    pub parents: Option<(KittyIndex, KittyIndex)>,
+    /// The block in which the kitty was created or bred
+    pub birth_block: BlockNumber,
+}
+
+/// A collection of kitties, e.g. a season or a special edition
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct Collection<BlockNumber> {
+    /// The most kitties of the collection that can exist at once, None for no limit
+    pub max_supply: Option<u32>,
+    /// `create_kitty` can mint into the collection from this block on
+    pub mint_start: BlockNumber,
+    /// `create_kitty` can't mint into the collection from this block on, None for no end
+    pub mint_end: Option<BlockNumber>,
+}
+
+/// An English auction of a kitty
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct Auction<AccountId, Balance, BlockNumber> {
+    pub seller: AccountId,
+    /// Bids below the reserve price are rejected
+    pub reserve_price: Balance,
+    /// The auction is settled at the start of this block
+    pub end: BlockNumber,
+    /// The highest bidder and bid, the bid is reserved from the bidder
+    pub best_bid: Option<(AccountId, Balance)>,
+}
+
+/// A Dutch auction of a kitty, the price decreases linearly from the start price to the floor
+/// price over the duration and stays at the floor price afterwards
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct DutchAuction<AccountId, Balance, BlockNumber> {
+    pub seller: AccountId,
+    pub start_price: Balance,
+    pub floor_price: Balance,
+    /// The block the auction was created in
+    pub start: BlockNumber,
+    /// How many blocks it takes the price to reach the floor price
+    pub duration: BlockNumber,
+}
+
+/// The account that minted or bred a kitty and the share of every sale it receives
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct Royalty<AccountId> {
+    pub creator: AccountId,
+    pub rate: Perbill,
+}
+
+/// The name of a kitty and the deposit reserved for it from the owner
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct KittyName<Balance> {
+    /// UTF-8 encoded
+    pub name: Vec<u8>,
+    pub deposit: Balance,
+}
+
+/// A standing offer to buy a kitty, the amount is reserved from the buyer
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct Offer<Balance, BlockNumber> {
+    pub amount: Balance,
+    /// The offer can't be accepted from this block on
+    pub expiry: BlockNumber,
+}
+
+/// A kitty offered as a gift, locked until the recipient accepts it or the offer closes
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct PendingTransfer<AccountId, BlockNumber> {
+    pub from: AccountId,
+    pub to: AccountId,
+    /// The kitty reverts to the sender at the start of this block
+    pub expiry: BlockNumber,
+}
+
+/// Terms an owner rents a kitty out on
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct RentalTerms<Balance, BlockNumber> {
+    pub price_per_block: Balance,
+    /// The longest a single rental can last
+    pub max_duration: BlockNumber,
+}
+
+/// A running rental, the renter can use the kitty but not sell it
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct Rental<AccountId, BlockNumber> {
+    pub renter: AccountId,
+    /// The kitty returns to its owner at the start of this block
+    pub end: BlockNumber,
+}
+
+/// A kitty locked in its own vault account and split into fungible shares
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct Vault<KittyId, Balance> {
+    pub kitty_id: KittyId,
+    /// All the shares of the vault, after a buyout the ones not claimed yet
+    pub total_shares: u32,
+    /// Anyone can buy the kitty out of the vault for this price, paid to the share holders
+    pub reserve_price: Balance,
+    /// The kitty was bought out and the holders can claim their part of what the vault account
+    /// received
+    pub bought_out: bool,
+}
+
+/// A staked kitty, locked until its staker unstakes it
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct Stake<AccountId, BlockNumber> {
+    pub staker: AccountId,
+    /// XP and rewards are credited up to this block
+    pub since: BlockNumber,
+}
+
+/// An open challenge of one kitty to another, the stake is reserved from the challenger
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct Challenge<AccountId, KittyId, Balance, BlockNumber> {
+    pub challenger: AccountId,
+    pub opponent: KittyId,
+    /// Each side stakes this much, the winner takes both
+    pub stake: Balance,
+    /// The challenge is withdrawn at the start of this block if not accepted by then
+    pub expiry: BlockNumber,
+}
+
+/// An accepted challenge, fought with the randomness of a later block. Both stakes are reserved
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct Battle<AccountId, KittyId, Balance> {
+    pub challenger: AccountId,
+    pub opponent: KittyId,
+    /// The account that accepted the challenge with the opponent
+    pub accepter: AccountId,
+    pub stake: Balance,
+}
+
+/// A battle in the history of a kitty
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct BattleRecord<KittyId, BlockNumber> {
+    pub opponent: KittyId,
+    pub won: bool,
+    pub block: BlockNumber,
+}
+
+// A value placed in storage that represents the current version of the pallet storage.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+    V1,
+    /// Kitty info is stored in `KittyInfos`
+    V2,
+    /// The kitties of every account are counted in `KittiesOwned`
+    V3,
+    /// Kitties are keyed by collection and token id
+    V4,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1
+    }
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
 pub enum KittyGender {
     Male,
@@ -30,12 +222,17 @@ pub enum KittyGender {
 
 impl Kitty {
     pub fn gender(&self) -> KittyGender {
-        if self.0[0] % 2 == 0 {
+        if self.0[phenotype::GENDER_GENE] % 2 == 0 {
             KittyGender::Male
         } else {
             KittyGender::Female
         }
     }
+
+    /// Decode the visible traits of the kitty from its DNA
+    pub fn phenotype(&self) -> Phenotype {
+        Phenotype::from_dna(&self.0)
+    }
 }
 
 #[frame_support::pallet]
@@ -51,12 +248,145 @@ pub mod pallet {
     {
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
         type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
-        type Currency: Currency<Self::AccountId>;
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// The algorithm `breed_kitties` uses to create the child.
+        type Breeder: Breeder;
+        /// Cooldown after the first breeding of a generation 0 kitty, it grows by the same amount
+        /// for every generation and every breeding.
+        #[pallet::constant]
+        type BaseBreedingCooldown: Get<Self::BlockNumber>;
+        /// The longest a kitty has to wait between breedings.
+        #[pallet::constant]
+        type MaxBreedingCooldown: Get<Self::BlockNumber>;
+        /// How many generations back the pedigree is checked for common ancestors when breeding.
+        /// 1 forbids parent / child and (half) siblings, 2 also grandparents, cousins, etc.
+        #[pallet::constant]
+        type InbreedingDepth: Get<u32>;
+        /// A bid landing less than this many blocks before the end of an auction pushes the end so
+        /// that this many blocks remain.
+        #[pallet::constant]
+        type AuctionExtension: Get<Self::BlockNumber>;
+        /// The longest an auction can run for.
+        #[pallet::constant]
+        type MaxAuctionDuration: Get<Self::BlockNumber>;
+        /// The most kitties a single account can own.
+        #[pallet::constant]
+        type MaxKittiesPerAccount: Get<u32>;
+        /// The origin allowed to create collections.
+        type CollectionOrigin: EnsureOrigin<Self::Origin>;
+        /// The most kitties that can exist at once, None for no limit.
+        #[pallet::constant]
+        type MaxTotalKitties: Get<Option<u32>>;
+        /// Reserved from the owner for every created or bred kitty, moves with the kitty to every
+        /// new owner.
+        #[pallet::constant]
+        type KittyDeposit: Get<BalanceOf<Self>>;
+        /// Reserved from the seller while a kitty is listed for sale with `set_price`.
+        #[pallet::constant]
+        type ListingDeposit: Get<BalanceOf<Self>>;
+        /// The longest name of a kitty, in bytes.
+        #[pallet::constant]
+        type MaxNameLength: Get<u32>;
+        /// Reserved from the owner of a named kitty, moves with the kitty to every new owner.
+        #[pallet::constant]
+        type NameDeposit: Get<BalanceOf<Self>>;
+        /// The highest royalty a creator can take from the sales of its kitties.
+        #[pallet::constant]
+        type MaxRoyalty: Get<Perbill>;
+        /// The cut of every sale taken by the marketplace, until changed with
+        /// `set_marketplace_fee`.
+        #[pallet::constant]
+        type MarketplaceFee: Get<Perbill>;
+        /// The marketplace fees are paid to the account derived from this id.
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+        /// Handler for the marketplace fees, e.g. [`FeeAccount`].
+        type OnMarketplaceFee: OnUnbalanced<NegativeImbalanceOf<Self>>;
+        /// How many standing offers a single kitty can have at once.
+        #[pallet::constant]
+        type MaxOffers: Get<u32>;
+        /// The longest an offer can stand before it expires.
+        #[pallet::constant]
+        type MaxOfferDuration: Get<Self::BlockNumber>;
+        /// The most kitties a single batch call can handle.
+        #[pallet::constant]
+        type MaxBatchSize: Get<u32>;
+        /// How long the recipient of a gifted kitty has to accept it.
+        #[pallet::constant]
+        type TransferOfferTimeout: Get<Self::BlockNumber>;
+        /// Experience points a staked kitty earns per block.
+        #[pallet::constant]
+        type XpPerBlock: Get<u64>;
+        /// Reward paid from the reward pot per block a kitty is staked.
+        #[pallet::constant]
+        type RewardPerBlock: Get<BalanceOf<Self>>;
+        /// How long both kitties have to rest after a battle.
+        #[pallet::constant]
+        type BattleCooldown: Get<Self::BlockNumber>;
+        /// The smallest stake a challenge can be made with.
+        #[pallet::constant]
+        type MinChallengeStake: Get<BalanceOf<Self>>;
+        /// How long a challenge stands before it expires and the stake is refunded.
+        #[pallet::constant]
+        type ChallengeTimeout: Get<Self::BlockNumber>;
+        /// How many open challenges a kitty can receive at once.
+        #[pallet::constant]
+        type MaxChallenges: Get<u32>;
+        /// How many of its latest battles are kept for a kitty.
+        #[pallet::constant]
+        type MaxBattleHistory: Get<u32>;
+        /// How many auctions, gift offers, rentals, challenges and battles can end in the same
+        /// block, bounding the work of `on_initialize`. Items ending in a full block end in the
+        /// next block with a free slot instead.
+        #[pallet::constant]
+        type MaxScheduledPerBlock: Get<u32>;
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     pub type KittyIndexOf<T> = <T as orml_nft::Config>::TokenId;
+    pub type ClassIdOf<T> = <T as orml_nft::Config>::ClassId;
+    /// A kitty is identified by its collection and its token id within the collection
+    pub type KittyIdOf<T> = (ClassIdOf<T>, KittyIndexOf<T>);
     pub type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+        <T as frame_system::Config>::AccountId,
+    >>::NegativeImbalance;
+    pub type KittyInfoOf<T> =
+        KittyInfo<KittyIdOf<T>, <T as frame_system::Config>::BlockNumber>;
+    pub type CollectionOf<T> = Collection<<T as frame_system::Config>::BlockNumber>;
+    pub type OfferOf<T> = Offer<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+    pub type PendingTransferOf<T> = PendingTransfer<
+        <T as frame_system::Config>::AccountId,
+        <T as frame_system::Config>::BlockNumber,
+    >;
+    pub type RentalTermsOf<T> = RentalTerms<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+    pub type RentalOf<T> =
+        Rental<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+    pub type VaultOf<T> = Vault<KittyIdOf<T>, BalanceOf<T>>;
+    pub type ChallengeOf<T> = Challenge<
+        <T as frame_system::Config>::AccountId,
+        KittyIdOf<T>,
+        BalanceOf<T>,
+        <T as frame_system::Config>::BlockNumber,
+    >;
+    pub type BattleOf<T> =
+        Battle<<T as frame_system::Config>::AccountId, KittyIdOf<T>, BalanceOf<T>>;
+    pub type BattleRecordOf<T> =
+        BattleRecord<KittyIdOf<T>, <T as frame_system::Config>::BlockNumber>;
+    pub type StakeOf<T> =
+        Stake<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+    pub type DutchAuctionOf<T> = DutchAuction<
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T>,
+        <T as frame_system::Config>::BlockNumber,
+    >;
+    pub type AuctionOf<T> = Auction<
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T>,
+        <T as frame_system::Config>::BlockNumber,
+    >;
 
     // stores all the kitties. Key is (user, kitty_id), value is Kitty
     // #[pallet::storage]
@@ -80,13 +410,289 @@ pub mod pallet {
     #[pallet::storage]
     #[pallet::getter(fn kitty_prices)]
     pub(super) type KittyPrices<T: Config> =
-        StorageMap<_, Blake2_128Concat, KittyIndexOf<T>, BalanceOf<T>, OptionQuery>;
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, BalanceOf<T>, OptionQuery>;
 
-    /// The class id for orml_nft
+    /// Get the fee to use a kitty as a sire. None means not available for siring.
+    #[pallet::storage]
+    #[pallet::getter(fn sire_prices)]
+    pub(super) type SirePrices<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// The class id for orml_nft of the collection created at genesis
     #[pallet::storage]
     #[pallet::getter(fn class_id)]
     pub type ClassId<T: Config> = StorageValue<_, T::ClassId, ValueQuery>;
 
+    /// Supply cap and mint window of every collection
+    #[pallet::storage]
+    #[pallet::getter(fn collections)]
+    pub type Collections<T: Config> =
+        StorageMap<_, Blake2_128Concat, ClassIdOf<T>, CollectionOf<T>, OptionQuery>;
+
+    /// Number of kitties in all the collections
+    #[pallet::storage]
+    #[pallet::getter(fn total_kitties)]
+    pub type TotalKitties<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Generation, parents and birth block of every kitty
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_info)]
+    pub type KittyInfos<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, KittyInfoOf<T>, OptionQuery>;
+
+    /// Breed count and cooldown of every kitty that has been bred
+    #[pallet::storage]
+    #[pallet::getter(fn breeding_state)]
+    pub type BreedingStates<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        KittyIdOf<T>,
+        BreedingState<T::BlockNumber>,
+        ValueQuery,
+    >;
+
+    /// Running auctions
+    #[pallet::storage]
+    #[pallet::getter(fn auctions)]
+    pub type Auctions<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, AuctionOf<T>, OptionQuery>;
+
+    /// Auctions to settle at the start of a block. Key is (end, kitty_id)
+    #[pallet::storage]
+    pub(super) type AuctionEndTime<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        Blake2_128Concat,
+        KittyIdOf<T>,
+        (),
+        OptionQuery,
+    >;
+
+    /// Running Dutch auctions
+    #[pallet::storage]
+    #[pallet::getter(fn dutch_auctions)]
+    pub type DutchAuctions<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, DutchAuctionOf<T>, OptionQuery>;
+
+    /// Number of kitties owned by every account
+    #[pallet::storage]
+    #[pallet::getter(fn kitties_owned)]
+    pub type KittiesOwned<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Deposit reserved from the owner of a kitty
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_deposits)]
+    pub type KittyDeposits<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// Depositor and deposit of a kitty listed for sale
+    #[pallet::storage]
+    #[pallet::getter(fn listing_deposits)]
+    pub type ListingDeposits<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+    /// Names of the named kitties
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_names)]
+    pub type KittyNames<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, KittyName<BalanceOf<T>>, OptionQuery>;
+
+    /// Creator and royalty rate of every kitty
+    #[pallet::storage]
+    #[pallet::getter(fn royalties)]
+    pub type Royalties<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, Royalty<T::AccountId>, OptionQuery>;
+
+    #[pallet::type_value]
+    pub fn DefaultMarketplaceFee<T: Config>() -> Perbill {
+        T::MarketplaceFee::get()
+    }
+
+    /// The cut of every sale taken by the marketplace
+    #[pallet::storage]
+    #[pallet::getter(fn marketplace_fee)]
+    pub type MarketplaceFeeRate<T: Config> =
+        StorageValue<_, Perbill, ValueQuery, DefaultMarketplaceFee<T>>;
+
+    /// Standing offers to buy a kitty, by kitty and buyer
+    #[pallet::storage]
+    #[pallet::getter(fn offers)]
+    pub type Offers<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        KittyIdOf<T>,
+        Blake2_128Concat,
+        T::AccountId,
+        OfferOf<T>,
+        OptionQuery,
+    >;
+
+    /// The account approved to transfer a kitty on behalf of its owner
+    #[pallet::storage]
+    #[pallet::getter(fn approvals)]
+    pub type Approvals<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, T::AccountId, OptionQuery>;
+
+    /// Kitties offered as gifts and waiting for the recipient
+    #[pallet::storage]
+    #[pallet::getter(fn pending_transfers)]
+    pub type PendingTransfers<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, PendingTransferOf<T>, OptionQuery>;
+
+    /// Gift offers to close at the start of a block. Key is (expiry, kitty_id)
+    #[pallet::storage]
+    pub(super) type PendingTransferExpiry<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        Blake2_128Concat,
+        KittyIdOf<T>,
+        (),
+        OptionQuery,
+    >;
+
+    /// Kitties their owners rent out
+    #[pallet::storage]
+    #[pallet::getter(fn rental_listings)]
+    pub type RentalListings<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, RentalTermsOf<T>, OptionQuery>;
+
+    /// Kitties currently rented out
+    #[pallet::storage]
+    #[pallet::getter(fn rentals)]
+    pub type Rentals<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, RentalOf<T>, OptionQuery>;
+
+    /// Rentals to end at the start of a block. Key is (end, kitty_id)
+    #[pallet::storage]
+    pub(super) type RentalEndTime<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        Blake2_128Concat,
+        KittyIdOf<T>,
+        (),
+        OptionQuery,
+    >;
+
+    /// Number of auctions, gift offers, rentals, challenges and battles ending in a block
+    #[pallet::storage]
+    pub(super) type ScheduledCount<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, u32, ValueQuery>;
+
+    /// The id of the next vault
+    #[pallet::storage]
+    #[pallet::getter(fn next_vault_id)]
+    pub type NextVaultId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Fractionalized kitties, kept until the kitty leaves the vault and all buyout proceeds are
+    /// claimed
+    #[pallet::storage]
+    #[pallet::getter(fn vaults)]
+    pub type Vaults<T: Config> = StorageMap<_, Twox64Concat, u32, VaultOf<T>, OptionQuery>;
+
+    /// The vault a kitty is locked in
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_vaults)]
+    pub type KittyVaults<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, u32, OptionQuery>;
+
+    /// Shares of a vault held by an account. Key is (vault_id, holder)
+    #[pallet::storage]
+    #[pallet::getter(fn shares)]
+    pub type Shares<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, u32, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Staked kitties
+    #[pallet::storage]
+    #[pallet::getter(fn staked_kitties)]
+    pub type StakedKitties<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, StakeOf<T>, OptionQuery>;
+
+    /// Experience points a kitty earned by staking, they stay with the kitty
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_xp)]
+    pub type KittyXp<T: Config> = StorageMap<_, Blake2_128Concat, KittyIdOf<T>, u64, ValueQuery>;
+
+    /// Open challenges, by challenging kitty
+    #[pallet::storage]
+    #[pallet::getter(fn challenges)]
+    pub type Challenges<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, ChallengeOf<T>, OptionQuery>;
+
+    /// Open challenges against a kitty. Key is (opponent_id, challenger_id)
+    #[pallet::storage]
+    pub(super) type ChallengesAgainst<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        KittyIdOf<T>,
+        Blake2_128Concat,
+        KittyIdOf<T>,
+        (),
+        OptionQuery,
+    >;
+
+    /// Challenges to withdraw at the start of a block. Key is (expiry, challenger_id)
+    #[pallet::storage]
+    pub(super) type ChallengeExpiry<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        Blake2_128Concat,
+        KittyIdOf<T>,
+        (),
+        OptionQuery,
+    >;
+
+    /// The first block a kitty can battle at
+    #[pallet::storage]
+    #[pallet::getter(fn battle_ready_at)]
+    pub type BattleReadyAt<T: Config> =
+        StorageMap<_, Blake2_128Concat, KittyIdOf<T>, T::BlockNumber, ValueQuery>;
+
+    /// Accepted challenges to fight at the start of a block. Key is (block, challenger_id)
+    #[pallet::storage]
+    #[pallet::getter(fn pending_battles)]
+    pub type PendingBattles<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        Blake2_128Concat,
+        KittyIdOf<T>,
+        BattleOf<T>,
+        OptionQuery,
+    >;
+
+    /// The latest battles of a kitty, oldest first and at most `MaxBattleHistory`
+    #[pallet::storage]
+    #[pallet::getter(fn battle_history)]
+    pub type BattleHistory<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        KittyIdOf<T>,
+        BoundedVec<BattleRecordOf<T>, T::MaxBattleHistory>,
+        ValueQuery,
+    >;
+
+    /// Operators allowed to transfer all the kitties of an owner. Key is (owner, operator)
+    #[pallet::storage]
+    #[pallet::getter(fn operators)]
+    pub type Operators<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::AccountId,
+        (),
+        OptionQuery,
+    >;
+
+    /// Storage version of the pallet.
+    #[pallet::storage]
+    pub(super) type StorageVersion<T: Config> = StorageValue<_, Releases, ValueQuery>;
+
     #[pallet::genesis_config]
     #[derive(Default)]
     pub struct GenesisConfig;
@@ -98,6 +704,25 @@ pub mod pallet {
             let class_id = orml_nft::Pallet::<T>::create_class(&Default::default(), Vec::new(), ())
                 .expect("Cannot fail or invalid chain spec");
             ClassId::<T>::put(class_id);
+            Collections::<T>::insert(
+                class_id,
+                Collection {
+                    max_supply: None,
+                    mint_start: Zero::zero(),
+                    mint_end: None,
+                },
+            );
+            StorageVersion::<T>::put(Releases::V4);
+
+            // keep the fee account alive, fees below the existential deposit of a missing account
+            // are burnt
+            let account_id = Pallet::<T>::account_id();
+            if T::Currency::free_balance(&account_id) < T::Currency::minimum_balance() {
+                let _ = T::Currency::make_free_balance_be(
+                    &account_id,
+                    T::Currency::minimum_balance(),
+                );
+            }
         }
     }
 
@@ -106,20 +731,177 @@ pub mod pallet {
     // T - runtime type which implements the Config
     pub struct Pallet<T>(_);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let mut settled = 0u32;
+            for (kitty_id, _) in AuctionEndTime::<T>::drain_prefix(now) {
+                if let Some(auction) = Auctions::<T>::take(kitty_id) {
+                    Self::settle_auction(kitty_id, auction);
+                }
+                settled = settled.saturating_add(1);
+            }
+            for (kitty_id, _) in PendingTransferExpiry::<T>::drain_prefix(now) {
+                if PendingTransfers::<T>::take(kitty_id).is_some() {
+                    Self::deposit_event(Event::TransferExpired(kitty_id));
+                }
+                settled = settled.saturating_add(1);
+            }
+            for (kitty_id, _) in RentalEndTime::<T>::drain_prefix(now) {
+                if let Some(rental) = Rentals::<T>::take(kitty_id) {
+                    // the sire offer and the challenges were made with the renter
+                    SirePrices::<T>::remove(kitty_id);
+                    Self::clear_challenges(kitty_id);
+                    Self::deposit_event(Event::RentalEnded(rental.renter, kitty_id));
+                }
+                settled = settled.saturating_add(1);
+            }
+            for (challenger_id, _) in ChallengeExpiry::<T>::drain_prefix(now) {
+                if let Some(challenge) = Challenges::<T>::take(challenger_id) {
+                    ChallengesAgainst::<T>::remove(challenge.opponent, challenger_id);
+                    T::Currency::unreserve(&challenge.challenger, challenge.stake);
+                    Self::deposit_event(Event::ChallengeExpired(
+                        challenge.challenger,
+                        challenger_id,
+                    ));
+                }
+                settled = settled.saturating_add(1);
+            }
+            for (challenger_id, battle) in PendingBattles::<T>::drain_prefix(now) {
+                Self::fight(challenger_id, battle, now);
+                settled = settled.saturating_add(1);
+            }
+            ScheduledCount::<T>::remove(now);
+            T::WeightInfo::on_initialize(settled)
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            migrations::migrate_to_v2::<T>()
+                .saturating_add(migrations::migrate_to_v3::<T>())
+                .saturating_add(migrations::migrate_to_v4::<T>())
+        }
+    }
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
-    #[pallet::metadata(T::AccountId = "AccountId", KittyIndexOf<T> = "KittyIndex", Option<BalanceOf<T>> = "Option<Balance>", BalanceOf<T> = "Balance")]
+    #[pallet::metadata(T::AccountId = "AccountId", Option<T::AccountId> = "Option<AccountId>", KittyIdOf<T> = "KittyId", ClassIdOf<T> = "ClassId", Option<BalanceOf<T>> = "Option<Balance>", BalanceOf<T> = "Balance", KittyInfoOf<T> = "KittyInfo", Option<RentalTermsOf<T>> = "Option<RentalTerms>", T::BlockNumber = "BlockNumber", Perbill = "Perbill")]
     pub enum Event<T: Config> {
         /// a kitty is created \[owner, kitty_id, kitty\]
-        KittyCreated(T::AccountId, KittyIndexOf<T>, Kitty),
-        /// a kitty is bred \[owner, kitty_id, kitty\]
-        KittyBred(T::AccountId, KittyIndexOf<T>, Kitty),
+        KittyCreated(T::AccountId, KittyIdOf<T>, Kitty),
+        /// a kitty is bred \[owner, kitty_id, kitty, info\]
+        KittyBred(T::AccountId, KittyIdOf<T>, Kitty, KittyInfoOf<T>),
         /// a kitty is transferred \[from,, to kitty_id\]
-        KittyTransferred(T::AccountId, T::AccountId, KittyIndexOf<T>),
+        KittyTransferred(T::AccountId, T::AccountId, KittyIdOf<T>),
         /// The price for a kitty is updated. \[owner, kitty_id, price\]
-        KittyPriceUpdated(T::AccountId, KittyIndexOf<T>, Option<BalanceOf<T>>),
+        KittyPriceUpdated(T::AccountId, KittyIdOf<T>, Option<BalanceOf<T>>),
         /// A kitty is sold. \[old_owner, new_owner, kitty_id, price\]
-        KittySold(T::AccountId, T::AccountId, KittyIndexOf<T>, BalanceOf<T>),
+        KittySold(T::AccountId, T::AccountId, KittyIdOf<T>, BalanceOf<T>),
+        /// The siring fee for a kitty is updated. \[owner, kitty_id, fee\]
+        SirePriceUpdated(T::AccountId, KittyIdOf<T>, Option<BalanceOf<T>>),
+        /// A siring fee is paid. \[payer, sire_owner, sire_id, fee\]
+        SireFeePaid(T::AccountId, T::AccountId, KittyIdOf<T>, BalanceOf<T>),
+        /// An auction is created. \[seller, kitty_id, reserve_price, end\]
+        AuctionCreated(T::AccountId, KittyIdOf<T>, BalanceOf<T>, T::BlockNumber),
+        /// A bid is placed. \[bidder, kitty_id, amount\]
+        BidPlaced(T::AccountId, KittyIdOf<T>, BalanceOf<T>),
+        /// A late bid extended the auction. \[kitty_id, end\]
+        AuctionExtended(KittyIdOf<T>, T::BlockNumber),
+        /// An auction ended with a winner. \[seller, winner, kitty_id, price\]
+        AuctionSettled(T::AccountId, T::AccountId, KittyIdOf<T>, BalanceOf<T>),
+        /// An auction was cancelled or ended without bids. \[seller, kitty_id\]
+        AuctionClosed(T::AccountId, KittyIdOf<T>),
+        /// A Dutch auction is created. \[seller, kitty_id, start_price, floor_price, duration\]
+        DutchAuctionCreated(
+            T::AccountId,
+            KittyIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            T::BlockNumber,
+        ),
+        /// An offer is made for a kitty. \[buyer, kitty_id, amount, expiry\]
+        OfferMade(T::AccountId, KittyIdOf<T>, BalanceOf<T>, T::BlockNumber),
+        /// An offer is withdrawn by the buyer. \[buyer, kitty_id\]
+        OfferWithdrawn(T::AccountId, KittyIdOf<T>),
+        /// An offer is accepted and the kitty sold. \[owner, buyer, kitty_id, amount\]
+        OfferAccepted(T::AccountId, T::AccountId, KittyIdOf<T>, BalanceOf<T>),
+        /// The creator changed the royalty of a kitty. \[creator, kitty_id, rate\]
+        RoyaltySet(T::AccountId, KittyIdOf<T>, Perbill),
+        /// A royalty is paid to the creator of a sold kitty. \[creator, kitty_id, amount\]
+        RoyaltyPaid(T::AccountId, KittyIdOf<T>, BalanceOf<T>),
+        /// The marketplace fee is changed. \[fee\]
+        MarketplaceFeeSet(Perbill),
+        /// The marketplace took its cut of a sale. \[kitty_id, amount\]
+        MarketplaceFeePaid(KittyIdOf<T>, BalanceOf<T>),
+        /// A kitty is burned by its owner. \[owner, kitty_id\]
+        KittyReleased(T::AccountId, KittyIdOf<T>),
+        /// A kitty is renamed, an empty name clears it. \[owner, kitty_id, name\]
+        KittyRenamed(T::AccountId, KittyIdOf<T>, Vec<u8>),
+        /// The URI in the token metadata of a kitty changed. \[owner, kitty_id, uri\]
+        KittyUriSet(T::AccountId, KittyIdOf<T>, Vec<u8>),
+        /// A collection is created. \[collection\]
+        CollectionCreated(ClassIdOf<T>),
+        /// The owner approved an account to transfer a kitty, None revokes the approval.
+        /// \[owner, kitty_id, spender\]
+        Approved(T::AccountId, KittyIdOf<T>, Option<T::AccountId>),
+        /// The owner allowed or disallowed an operator to transfer all its kitties.
+        /// \[owner, operator, approved\]
+        ApprovedForAll(T::AccountId, T::AccountId, bool),
+        /// A kitty is offered as a gift. \[from, to, kitty_id, expiry\]
+        TransferOffered(T::AccountId, T::AccountId, KittyIdOf<T>, T::BlockNumber),
+        /// The recipient turned down a gift. \[to, kitty_id\]
+        TransferRejected(T::AccountId, KittyIdOf<T>),
+        /// The sender took back a gift offer. \[from, kitty_id\]
+        TransferCancelled(T::AccountId, KittyIdOf<T>),
+        /// A gift was not accepted in time and the kitty stays with the sender. \[kitty_id\]
+        TransferExpired(KittyIdOf<T>),
+        /// The owner changed the rental terms of a kitty, None stops renting it out.
+        /// \[owner, kitty_id, terms\]
+        RentalTermsSet(T::AccountId, KittyIdOf<T>, Option<RentalTermsOf<T>>),
+        /// A kitty is rented. \[renter, kitty_id, end, price\]
+        KittyRented(T::AccountId, KittyIdOf<T>, T::BlockNumber, BalanceOf<T>),
+        /// A rental ended and the kitty is back with its owner. \[renter, kitty_id\]
+        RentalEnded(T::AccountId, KittyIdOf<T>),
+        /// A kitty is locked in a vault and split into shares.
+        /// \[owner, kitty_id, vault_id, shares\]
+        KittyFractionalized(T::AccountId, KittyIdOf<T>, u32, u32),
+        /// Shares of a vault changed hands. \[from, to, vault_id, amount\]
+        SharesTransferred(T::AccountId, T::AccountId, u32, u32),
+        /// The holder of all the shares took the kitty out of the vault. \[holder, vault_id\]
+        KittyRedeemed(T::AccountId, u32),
+        /// A kitty is bought out of its vault. \[buyer, vault_id, price\]
+        KittyBoughtOut(T::AccountId, u32, BalanceOf<T>),
+        /// A share holder claimed its part of a buyout. \[holder, vault_id, amount\]
+        BuyoutClaimed(T::AccountId, u32, BalanceOf<T>),
+        /// A kitty is staked. \[staker, kitty_id\]
+        KittyStaked(T::AccountId, KittyIdOf<T>),
+        /// A kitty is unstaked. \[staker, kitty_id\]
+        KittyUnstaked(T::AccountId, KittyIdOf<T>),
+        /// XP and rewards of a staked kitty are credited. \[staker, kitty_id, xp, reward\]
+        StakingRewarded(T::AccountId, KittyIdOf<T>, u64, BalanceOf<T>),
+        /// The reward pot is topped up. \[funder, amount\]
+        RewardPotFunded(T::AccountId, BalanceOf<T>),
+        /// A kitty challenges another one until the expiry block.
+        /// \[challenger, kitty_id, opponent_id, stake, expiry\]
+        ChallengeIssued(
+            T::AccountId,
+            KittyIdOf<T>,
+            KittyIdOf<T>,
+            BalanceOf<T>,
+            T::BlockNumber,
+        ),
+        /// A challenge is withdrawn. \[challenger, kitty_id\]
+        ChallengeCancelled(T::AccountId, KittyIdOf<T>),
+        /// The user of the challenged kitty turned down a challenge. \[rejecter, challenger_id\]
+        ChallengeRejected(T::AccountId, KittyIdOf<T>),
+        /// A challenge was not accepted in time and the stake is refunded.
+        /// \[challenger, kitty_id\]
+        ChallengeExpired(T::AccountId, KittyIdOf<T>),
+        /// A challenge is accepted, the battle is fought at the start of the given block.
+        /// \[accepter, challenger_id, opponent_id, block\]
+        ChallengeAccepted(T::AccountId, KittyIdOf<T>, KittyIdOf<T>, T::BlockNumber),
+        /// A battle is fought and the winner takes the stake of the loser.
+        /// \[winner, winner_id, loser_id, stake\]
+        BattleFought(T::AccountId, KittyIdOf<T>, KittyIdOf<T>, BalanceOf<T>),
     }
 
     #[pallet::error]
@@ -130,20 +912,143 @@ pub mod pallet {
         NotForSale,
         PriceTooLow,
         BuyFromSelf,
+        /// One of the kitties has bred recently and can't breed yet
+        KittyOnCooldown,
+        /// The kitties are too closely related to breed
+        Inbreeding,
+        /// The kitty is not offered as a sire
+        NotForSiring,
+        /// Only male kitties can be offered as sires
+        NotMale,
+        /// The kitty is being auctioned and can't change hands
+        KittyInAuction,
+        /// The kitty is not being auctioned
+        AuctionNotFound,
+        /// The auction end must be in the future and within `MaxAuctionDuration`
+        InvalidAuctionEnd,
+        /// The bid is below the reserve price or not above the best bid
+        BidTooLow,
+        /// An auction can't be cancelled once it has bids
+        AuctionHasBids,
+        /// The floor price must not exceed the start price and the duration must not be zero
+        InvalidDutchAuction,
+        /// There is no offer from this buyer for the kitty
+        OfferNotFound,
+        /// The offer is past its expiry
+        OfferExpired,
+        /// The kitty has reached the maximum number of offers
+        TooManyOffers,
+        /// The offer is below the existential deposit
+        OfferTooLow,
+        /// The offer expires further away than `MaxOfferDuration`
+        OfferExpiryTooFar,
+        /// Only the creator of a kitty can set its royalty
+        NotCreator,
+        /// The royalty is above the maximum
+        RoyaltyTooHigh,
+        /// The royalty can't be raised once the kitty left its creator
+        RoyaltyIncrease,
+        /// The account owns the maximum number of kitties
+        TooManyKittiesOwned,
+        /// The maximum number of kitties exist
+        MaxTotalKittiesReached,
+        /// The name is longer than the maximum
+        NameTooLong,
+        /// The URI is longer than the token metadata allows
+        UriTooLong,
+        /// Names and URIs must be valid UTF-8
+        InvalidUtf8,
+        /// There is no collection with this id
+        CollectionNotFound,
+        /// The collection is outside of its mint window
+        MintWindowClosed,
+        /// The collection has reached its supply cap
+        CollectionSupplyReached,
+        /// The mint window must end after it starts
+        InvalidMintWindow,
+        /// The sender is neither the owner of the kitty, nor approved, nor an operator
+        NotApproved,
+        /// The batch has more than `MaxBatchSize` entries
+        BatchTooLarge,
+        /// The kitty is offered as a gift and locked until the offer closes
+        KittyPendingTransfer,
+        /// The kitty is not offered as a gift
+        TransferNotFound,
+        /// Only the recipient of a gift can accept or reject it
+        NotRecipient,
+        /// The kitty is rented out and can't change hands
+        KittyRented,
+        /// The kitty is not offered for rent
+        NotForRent,
+        /// The rental duration must not be zero nor above the maximum of the terms
+        InvalidRentalDuration,
+        /// The owner can't rent its own kitty
+        RentFromSelf,
+        /// A kitty must be split into at least one share and transfers must move some
+        InvalidShares,
+        /// There is no vault with this id
+        VaultNotFound,
+        /// The account doesn't hold enough shares of the vault
+        InsufficientShares,
+        /// The kitty was already bought out of the vault
+        VaultBoughtOut,
+        /// The kitty is still in the vault
+        NotBoughtOut,
+        /// The kitty is staked and locked until unstaked
+        KittyStaked,
+        /// The kitty is not staked by the sender
+        NotStaked,
+        /// A kitty can't battle itself
+        CannotBattleSelf,
+        /// The kitty already has an open challenge
+        ChallengeExists,
+        /// The kitty has no open challenge
+        ChallengeNotFound,
+        /// The opponent has reached the maximum number of open challenges
+        TooManyChallenges,
+        /// The stake is below `MinChallengeStake`
+        ChallengeStakeTooLow,
+        /// One of the kitties has battled recently and can't battle yet
+        KittyOnBattleCooldown,
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        /// Create a new kitty
-        #[pallet::weight(1000)]
-        pub fn create_kitty(origin: OriginFor<T>) -> DispatchResult {
+        /// Create a new kitty in a collection within its mint window
+        #[pallet::weight(T::WeightInfo::create_kitty())]
+        #[transactional]
+        pub fn create_kitty(origin: OriginFor<T>, collection: ClassIdOf<T>) -> DispatchResult {
             let sender = ensure_signed(origin)?;
+
+            let info = Self::collections(collection).ok_or(Error::<T>::CollectionNotFound)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                now >= info.mint_start && info.mint_end.map_or(true, |end| now < end),
+                Error::<T>::MintWindowClosed
+            );
+
             let dna = Self::random_value(&sender);
 
             // Create and store kitty
             let kitty = Kitty(dna);
-            let kitty_id =
-                orml_nft::Pallet::<T>::mint(&sender, Self::class_id(), Vec::new(), kitty.clone())?;
+            let kitty_id = Self::mint_kitty(&sender, collection, kitty.clone())?;
+
+            KittyInfos::<T>::insert(
+                kitty_id,
+                KittyInfo {
+                    generation: 0,
+                    parents: None,
+                    birth_block: now,
+                },
+            );
+            Self::reserve_kitty_deposit(&sender, kitty_id)?;
+            Royalties::<T>::insert(
+                kitty_id,
+                Royalty {
+                    creator: sender.clone(),
+                    rate: Perbill::zero(),
+                },
+            );
 
             // Emit an event
             Self::deposit_event(Event::KittyCreated(sender, kitty_id, kitty));
@@ -152,53 +1057,31 @@ pub mod pallet {
         }
 
         /// Breed kitties
-        #[pallet::weight(1000)]
+        #[pallet::weight(T::WeightInfo::breed_kitties()
+            .saturating_add(T::DbWeight::get().reads(Pallet::<T>::pedigree_reads())))]
+        #[transactional]
         pub fn breed_kitties(
             origin: OriginFor<T>,
-            kitty_id_1: KittyIndexOf<T>,
-            kitty_id_2: KittyIndexOf<T>,
+            kitty_id_1: KittyIdOf<T>,
+            kitty_id_2: KittyIdOf<T>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            let kitty1 = Self::kitties(&sender, kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
-            let kitty2 = Self::kitties(&sender, kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
-
-            ensure!(kitty1.gender() != kitty2.gender(), Error::<T>::SameGender);
-
-            // let next_kitty_id: KittyIndexOf<T> = Self::get_next_kitty_id()?;
-
-            let kitty1_dna = kitty1.0;
-            let kitty2_dna = kitty2.0;
+            let kitty1 =
+                Self::usable_kitty(&sender, kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
+            let kitty2 =
+                Self::usable_kitty(&sender, kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
 
-            let selector = Self::random_value(&sender);
-
-            let mut new_dna = [0u8; 16];
-            // Combine parents and selector to create new kitty
-            for i in 0..kitty1_dna.len() {
-                new_dna[i] = combine_dna(kitty1_dna[i], kitty2_dna[i], selector[i]);
-            }
-
-            let new_kitty = Kitty(new_dna);
-
-            // Kitties::<T>::insert(&sender, next_kitty_id, &new_kitty);
-            let kitty_id = orml_nft::Pallet::<T>::mint(
-                &sender,
-                Self::class_id(),
-                Vec::new(),
-                new_kitty.clone(),
-            )?;
-
-            Self::deposit_event(Event::KittyBred(sender, kitty_id, new_kitty));
-
-            Ok(())
+            Self::do_breed(&sender, kitty_id_1, &kitty1, kitty_id_2, &kitty2)
         }
 
         /// Create a new kitty
-        #[pallet::weight(1000)]
+        #[pallet::weight(T::WeightInfo::transfer(T::MaxOffers::get(), T::MaxChallenges::get()))]
+        #[transactional]
         pub fn transfer(
             origin: OriginFor<T>,
             to: T::AccountId,
-            kitty_id: KittyIndexOf<T>,
+            kitty_id: KittyIdOf<T>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
@@ -221,23 +1104,15 @@ pub mod pallet {
             //     Ok(())
             // })
 
-            orml_nft::Pallet::<T>::transfer(&sender, &to, (Self::class_id(), kitty_id))?;
-
-            // remove the price after transferring
-            if sender != to {
-                KittyPrices::<T>::remove(kitty_id);
-                Self::deposit_event(Event::KittyTransferred(sender, to, kitty_id));
-            }
-
-            Ok(())
+            Self::do_transfer(&sender, &to, kitty_id)
         }
 
         // Set a price for a kitty for sale
         /// None to delist the kitty
-        #[pallet::weight(1000)]
+        #[pallet::weight(T::WeightInfo::set_price())]
         pub fn set_price(
             origin: OriginFor<T>,
-            kitty_id: KittyIndexOf<T>,
+            kitty_id: KittyIdOf<T>,
             new_price: Option<BalanceOf<T>>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
@@ -247,33 +1122,23 @@ pub mod pallet {
             //     Kitties::<T>::contains_key(&sender, kitty_id),
             //     Error::<T>::NotOwner
             // );
-            ensure!(
-                orml_nft::TokensByOwner::<T>::contains_key(&sender, (Self::class_id(), kitty_id)),
-                Error::<T>::NotOwner
-            );
-
-            KittyPrices::<T>::mutate_exists(kitty_id, |price|
-            // deletes from the storage if new_price is None 
-                                            *price = new_price);
-
-            Self::deposit_event(Event::KittyPriceUpdated(sender, kitty_id, new_price));
-
-            Ok(())
+            Self::do_set_price(&sender, kitty_id, new_price)
         }
 
         /// Buy a kitty
-        #[pallet::weight(1000)]
+        #[pallet::weight(T::WeightInfo::buy(T::MaxOffers::get()))]
         #[transactional]
         pub fn buy(
             origin: OriginFor<T>,
             owner: T::AccountId,
-            kitty_id: KittyIndexOf<T>,
+            kitty_id: KittyIdOf<T>,
             max_price: BalanceOf<T>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
             // don't buy your own kitty or kitty will end up with no owner
             ensure!(sender != owner, Error::<T>::BuyFromSelf);
+            Self::ensure_tradable(kitty_id)?;
 
             // take is a read and delete (removes the kitty from the old owner)
             // now we know this kitty belongs to the tx sender
@@ -285,14 +1150,10 @@ pub mod pallet {
 
                 ensure!(max_price >= price, Error::<T>::PriceTooLow);
 
-                orml_nft::Pallet::<T>::transfer(&owner, &sender, (Self::class_id(), kitty_id))?;
+                orml_nft::Pallet::<T>::transfer(&owner, &sender, kitty_id)?;
+                Self::owner_changed(kitty_id, &owner, &sender)?;
 
-                T::Currency::transfer(
-                    &sender, // from
-                    &owner,  // to
-                    price,
-                    ExistenceRequirement::KeepAlive, // do NOT kill the sender account
-                )?;
+                Self::pay_for_kitty(&sender, &owner, kitty_id, price, false)?;
 
                 // transfer the kitty AFTER transferring the money
                 // Kitties::<T>::insert(&sender, kitty_id, kitty);
@@ -302,49 +1163,1835 @@ pub mod pallet {
                 Ok(())
             })
         }
-    }
 
-    fn combine_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
-        // selector[bit_index] == 0 -> use dna1[bit_index]
-        // selector[bit_index] == 1 -> use dna2[bit_index]
-        // e.g.
-        // selector     = 0b00000001
-        // dna1		= 0b10101010
-        // dna2		= 0b00001111
-        // result	= 0b10101011
+        /// Offer a male kitty as a sire for other accounts to breed with
+        /// None to stop offering the kitty
+        #[pallet::weight(T::WeightInfo::set_sire_price())]
+        pub fn set_sire_price(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            new_price: Option<BalanceOf<T>>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
 
-        (!selector & dna1) | (selector & dna2)
-    }
-}
+            let kitty = Self::usable_kitty(&sender, kitty_id).ok_or(Error::<T>::NotOwner)?;
+            ensure!(kitty.gender() == KittyGender::Male, Error::<T>::NotMale);
 
-impl<T: Config> Pallet<T> {
-    fn kitties(owner: &T::AccountId, kitty_id: KittyIndexOf<T>) -> Option<Kitty> {
-        orml_nft::Pallet::<T>::tokens(Self::class_id(), kitty_id).and_then(|x| {
-            if x.owner == *owner {
-                Some(x.data)
-            } else {
-                None
-            }
-        })
-    }
+            SirePrices::<T>::mutate_exists(kitty_id, |price| *price = new_price);
 
-    // fn get_next_kitty_id() -> Result<KittyIndexOf<T>, DispatchError> {
-    //     NextKittyId::<T>::try_mutate(|current_id_ptr| -> Result<KittyIndexOf<T>, DispatchError> {
-    //         let current_id = *current_id_ptr;
-    //         *current_id_ptr = current_id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
-    //         Ok(current_id)
-    //     })
-    // }
+            Self::deposit_event(Event::SirePriceUpdated(sender, kitty_id, new_price));
 
-    // fn get_next_kitty_id() -> Result<KittyIndexOf<T>, DispatchError> {
-    //     NextKittyId::<T>::try_mutate(|next_id| -> Result<KittyIndexOf<T>, DispatchError> {
-    //         let current_id = *next_id;
-    //         *next_id = next_id
-    //             .checked_add(&One::one())
-    //             .ok_or(ArithmeticError::Overflow)?;
-    //         Ok(current_id)
-    //     })
-    // }
+            Ok(())
+        }
+
+        /// Breed your kitty with a sire offered by another account, paying the siring fee
+        /// The child belongs to the sender
+        #[pallet::weight(T::WeightInfo::breed_with_sire()
+            .saturating_add(T::DbWeight::get().reads(Pallet::<T>::pedigree_reads())))]
+        #[transactional]
+        pub fn breed_with_sire(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            sire_id: KittyIdOf<T>,
+            max_fee: BalanceOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let kitty = Self::usable_kitty(&sender, kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+            let sire = orml_nft::Pallet::<T>::tokens(sire_id.0, sire_id.1)
+                .ok_or(Error::<T>::InvalidKittyId)?;
+            // the fee goes to whoever has the breeding rights on the sire
+            let sire_user = Self::rentals(sire_id).map_or(sire.owner, |rental| rental.renter);
+
+            let fee = Self::sire_prices(sire_id).ok_or(Error::<T>::NotForSiring)?;
+            ensure!(max_fee >= fee, Error::<T>::PriceTooLow);
+
+            T::Currency::transfer(&sender, &sire_user, fee, ExistenceRequirement::KeepAlive)?;
+
+            Self::do_breed(&sender, kitty_id, &kitty, sire_id, &sire.data)?;
+
+            Self::deposit_event(Event::SireFeePaid(sender, sire_user, sire_id, fee));
+
+            Ok(())
+        }
+
+        /// Put a kitty up for an English auction ending at the start of block `end`, or of the
+        /// next block with a free slot
+        /// The highest bid at the end buys the kitty
+        #[pallet::weight(T::WeightInfo::create_auction())]
+        pub fn create_auction(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            reserve_price: BalanceOf<T>,
+            end: T::BlockNumber,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                orml_nft::TokensByOwner::<T>::contains_key(&sender, kitty_id),
+                Error::<T>::NotOwner
+            );
+            Self::ensure_tradable(kitty_id)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                end > now && end <= now.saturating_add(T::MaxAuctionDuration::get()),
+                Error::<T>::InvalidAuctionEnd
+            );
+            let end = Self::schedule(end);
+
+            // an auctioned kitty can't be bought at a fixed price
+            Self::delist(kitty_id);
+
+            Auctions::<T>::insert(
+                kitty_id,
+                Auction {
+                    seller: sender.clone(),
+                    reserve_price,
+                    end,
+                    best_bid: None,
+                },
+            );
+            AuctionEndTime::<T>::insert(end, kitty_id, ());
+
+            Self::deposit_event(Event::AuctionCreated(sender, kitty_id, reserve_price, end));
+
+            Ok(())
+        }
+
+        /// Bid on an auctioned kitty, the bid is reserved until outbid or the auction ends
+        #[pallet::weight(T::WeightInfo::bid())]
+        #[transactional]
+        pub fn bid(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Auctions::<T>::try_mutate(kitty_id, |auction| -> DispatchResult {
+                let auction = auction.as_mut().ok_or(Error::<T>::AuctionNotFound)?;
+
+                ensure!(sender != auction.seller, Error::<T>::BuyFromSelf);
+                ensure!(amount >= auction.reserve_price, Error::<T>::BidTooLow);
+
+                // release the funds of the outbid bidder
+                if let Some((bidder, best_bid)) = &auction.best_bid {
+                    ensure!(amount > *best_bid, Error::<T>::BidTooLow);
+                    T::Currency::unreserve(bidder, *best_bid);
+                }
+                T::Currency::reserve(&sender, amount)?;
+                auction.best_bid = Some((sender.clone(), amount));
+
+                // a late bid gives the other bidders time to answer
+                let now = frame_system::Pallet::<T>::block_number();
+                let extension = T::AuctionExtension::get();
+                if auction.end.saturating_sub(now) < extension {
+                    AuctionEndTime::<T>::remove(auction.end, kitty_id);
+                    Self::unschedule(auction.end);
+                    auction.end = Self::schedule(now.saturating_add(extension));
+                    AuctionEndTime::<T>::insert(auction.end, kitty_id, ());
+
+                    Self::deposit_event(Event::AuctionExtended(kitty_id, auction.end));
+                }
+
+                Self::deposit_event(Event::BidPlaced(sender, kitty_id, amount));
+
+                Ok(())
+            })
+        }
+
+        /// Cancel an auction that has no bids yet
+        #[pallet::weight(T::WeightInfo::cancel_auction())]
+        pub fn cancel_auction(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let auction = Self::auctions(kitty_id).ok_or(Error::<T>::AuctionNotFound)?;
+            ensure!(auction.seller == sender, Error::<T>::NotOwner);
+            ensure!(auction.best_bid.is_none(), Error::<T>::AuctionHasBids);
+
+            Auctions::<T>::remove(kitty_id);
+            AuctionEndTime::<T>::remove(auction.end, kitty_id);
+            Self::unschedule(auction.end);
+
+            Self::deposit_event(Event::AuctionClosed(sender, kitty_id));
+
+            Ok(())
+        }
+
+        /// Put a kitty up for a Dutch auction, its price drops every block from `start_price`
+        /// until it reaches `floor_price` after `duration` blocks
+        #[pallet::weight(T::WeightInfo::create_dutch_auction())]
+        pub fn create_dutch_auction(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            start_price: BalanceOf<T>,
+            floor_price: BalanceOf<T>,
+            duration: T::BlockNumber,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                orml_nft::TokensByOwner::<T>::contains_key(&sender, kitty_id),
+                Error::<T>::NotOwner
+            );
+            Self::ensure_tradable(kitty_id)?;
+            ensure!(
+                floor_price <= start_price && !duration.is_zero(),
+                Error::<T>::InvalidDutchAuction
+            );
+
+            // an auctioned kitty can't be bought at a fixed price
+            Self::delist(kitty_id);
+
+            DutchAuctions::<T>::insert(
+                kitty_id,
+                DutchAuction {
+                    seller: sender.clone(),
+                    start_price,
+                    floor_price,
+                    start: frame_system::Pallet::<T>::block_number(),
+                    duration,
+                },
+            );
+
+            Self::deposit_event(Event::DutchAuctionCreated(
+                sender,
+                kitty_id,
+                start_price,
+                floor_price,
+                duration,
+            ));
+
+            Ok(())
+        }
+
+        /// Buy a kitty from a Dutch auction at the price of the current block
+        #[pallet::weight(T::WeightInfo::buy_dutch(T::MaxOffers::get()))]
+        #[transactional]
+        pub fn buy_dutch(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            max_price: BalanceOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let auction = DutchAuctions::<T>::take(kitty_id).ok_or(Error::<T>::AuctionNotFound)?;
+            ensure!(sender != auction.seller, Error::<T>::BuyFromSelf);
+
+            let price = Self::dutch_price(&auction, frame_system::Pallet::<T>::block_number());
+            ensure!(max_price >= price, Error::<T>::PriceTooLow);
+
+            orml_nft::Pallet::<T>::transfer(&auction.seller, &sender, kitty_id)?;
+            Self::owner_changed(kitty_id, &auction.seller, &sender)?;
+
+            Self::pay_for_kitty(&sender, &auction.seller, kitty_id, price, false)?;
+
+            Self::deposit_event(Event::KittySold(auction.seller, sender, kitty_id, price));
+
+            Ok(())
+        }
+
+        /// Stop a Dutch auction
+        #[pallet::weight(T::WeightInfo::cancel_dutch_auction())]
+        pub fn cancel_dutch_auction(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let auction = Self::dutch_auctions(kitty_id).ok_or(Error::<T>::AuctionNotFound)?;
+            ensure!(auction.seller == sender, Error::<T>::NotOwner);
+
+            DutchAuctions::<T>::remove(kitty_id);
+
+            Self::deposit_event(Event::AuctionClosed(sender, kitty_id));
+
+            Ok(())
+        }
+
+        /// Offer to buy a kitty that is not necessarily for sale, the amount is reserved until
+        /// the offer is accepted or withdrawn
+        /// Making a new offer for the same kitty replaces the previous one
+        /// Expired offers are refunded to make room for new ones
+        #[pallet::weight(T::WeightInfo::make_offer(T::MaxOffers::get()))]
+        #[transactional]
+        pub fn make_offer(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            amount: BalanceOf<T>,
+            expiry: T::BlockNumber,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let token = orml_nft::Pallet::<T>::tokens(kitty_id.0, kitty_id.1)
+                .ok_or(Error::<T>::InvalidKittyId)?;
+            ensure!(sender != token.owner, Error::<T>::BuyFromSelf);
+            ensure!(
+                !amount.is_zero() && amount >= T::Currency::minimum_balance(),
+                Error::<T>::OfferTooLow
+            );
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(expiry > now, Error::<T>::OfferExpired);
+            ensure!(
+                expiry <= now.saturating_add(T::MaxOfferDuration::get()),
+                Error::<T>::OfferExpiryTooFar
+            );
+
+            match Self::offers(kitty_id, &sender) {
+                Some(previous) => {
+                    T::Currency::unreserve(&sender, previous.amount);
+                }
+                None => {
+                    ensure!(
+                        Self::prune_expired_offers(kitty_id, now) < T::MaxOffers::get(),
+                        Error::<T>::TooManyOffers
+                    );
+                }
+            }
+
+            T::Currency::reserve(&sender, amount)?;
+            Offers::<T>::insert(kitty_id, &sender, Offer { amount, expiry });
+
+            Self::deposit_event(Event::OfferMade(sender, kitty_id, amount, expiry));
+
+            Ok(())
+        }
+
+        /// Withdraw an offer, releasing the reserved amount
+        #[pallet::weight(T::WeightInfo::withdraw_offer())]
+        pub fn withdraw_offer(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let offer = Offers::<T>::take(kitty_id, &sender).ok_or(Error::<T>::OfferNotFound)?;
+            T::Currency::unreserve(&sender, offer.amount);
+
+            Self::deposit_event(Event::OfferWithdrawn(sender, kitty_id));
+
+            Ok(())
+        }
+
+        /// Sell a kitty to a buyer that made an offer for it
+        /// `min_amount` protects against the buyer lowering the offer in the meantime
+        #[pallet::weight(T::WeightInfo::accept_offer(T::MaxOffers::get()))]
+        #[transactional]
+        pub fn accept_offer(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            buyer: T::AccountId,
+            min_amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                orml_nft::TokensByOwner::<T>::contains_key(&sender, kitty_id),
+                Error::<T>::NotOwner
+            );
+            Self::ensure_tradable(kitty_id)?;
+
+            let offer = Offers::<T>::take(kitty_id, &buyer).ok_or(Error::<T>::OfferNotFound)?;
+            ensure!(
+                offer.expiry > frame_system::Pallet::<T>::block_number(),
+                Error::<T>::OfferExpired
+            );
+            ensure!(offer.amount >= min_amount, Error::<T>::PriceTooLow);
+
+            orml_nft::Pallet::<T>::transfer(&sender, &buyer, kitty_id)?;
+            Self::pay_for_kitty(&buyer, &sender, kitty_id, offer.amount, true)?;
+            Self::owner_changed(kitty_id, &sender, &buyer)?;
+
+            Self::deposit_event(Event::OfferAccepted(sender, buyer, kitty_id, offer.amount));
+
+            Ok(())
+        }
+
+        /// Set the share of every future sale of a kitty paid to its creator
+        /// Once the kitty left its creator the royalty can only be lowered, so a creator can't
+        /// raise it just before a sale
+        #[pallet::weight(T::WeightInfo::set_royalty())]
+        pub fn set_royalty(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            rate: Perbill,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(rate <= T::MaxRoyalty::get(), Error::<T>::RoyaltyTooHigh);
+
+            Royalties::<T>::try_mutate(kitty_id, |royalty| -> DispatchResult {
+                let royalty = royalty.as_mut().ok_or(Error::<T>::NotCreator)?;
+                ensure!(royalty.creator == sender, Error::<T>::NotCreator);
+                ensure!(
+                    rate <= royalty.rate
+                        || orml_nft::TokensByOwner::<T>::contains_key(&sender, kitty_id),
+                    Error::<T>::RoyaltyIncrease
+                );
+                royalty.rate = rate;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::RoyaltySet(sender, kitty_id, rate));
+
+            Ok(())
+        }
+
+        /// Burn a kitty, refunding its deposit
+        /// The pedigree of the kitty is kept for its descendants
+        #[pallet::weight(T::WeightInfo::release_kitty(
+            T::MaxOffers::get(),
+            T::MaxChallenges::get()
+        ))]
+        #[transactional]
+        pub fn release_kitty(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                orml_nft::TokensByOwner::<T>::contains_key(&sender, kitty_id),
+                Error::<T>::NotOwner
+            );
+            Self::ensure_tradable(kitty_id)?;
+
+            Self::clear_listings(kitty_id);
+            orml_nft::Pallet::<T>::burn(&sender, kitty_id)?;
+            KittiesOwned::<T>::mutate(&sender, |count| *count = count.saturating_sub(1));
+            TotalKitties::<T>::mutate(|total| *total = total.saturating_sub(1));
+
+            if let Some(deposit) = KittyDeposits::<T>::take(kitty_id) {
+                T::Currency::unreserve(&sender, deposit);
+            }
+            if let Some(name) = KittyNames::<T>::take(kitty_id) {
+                T::Currency::unreserve(&sender, name.deposit);
+            }
+            BreedingStates::<T>::remove(kitty_id);
+            KittyXp::<T>::remove(kitty_id);
+            BattleReadyAt::<T>::remove(kitty_id);
+            BattleHistory::<T>::remove(kitty_id);
+            Royalties::<T>::remove(kitty_id);
+            Approvals::<T>::remove(kitty_id);
+
+            Self::deposit_event(Event::KittyReleased(sender, kitty_id));
+
+            Ok(())
+        }
+
+        /// Name a kitty, reserving the name deposit from the owner on the first naming
+        /// An empty name clears the name and refunds the deposit
+        #[pallet::weight(T::WeightInfo::set_kitty_name())]
+        pub fn set_kitty_name(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            name: Vec<u8>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                orml_nft::TokensByOwner::<T>::contains_key(&sender, kitty_id),
+                Error::<T>::NotOwner
+            );
+            ensure!(name.len() as u32 <= T::MaxNameLength::get(), Error::<T>::NameTooLong);
+            ensure!(sp_std::str::from_utf8(&name).is_ok(), Error::<T>::InvalidUtf8);
+
+            if name.is_empty() {
+                if let Some(previous) = KittyNames::<T>::take(kitty_id) {
+                    T::Currency::unreserve(&sender, previous.deposit);
+                }
+            } else {
+                let deposit = match Self::kitty_names(kitty_id) {
+                    Some(previous) => previous.deposit,
+                    None => {
+                        let deposit = T::NameDeposit::get();
+                        T::Currency::reserve(&sender, deposit)?;
+                        deposit
+                    }
+                };
+                KittyNames::<T>::insert(
+                    kitty_id,
+                    KittyName {
+                        name: name.clone(),
+                        deposit,
+                    },
+                );
+            }
+
+            Self::deposit_event(Event::KittyRenamed(sender, kitty_id, name));
+
+            Ok(())
+        }
+
+        /// Store a URI, e.g. of an image of the kitty, in its token metadata
+        /// The length is bounded by the token metadata limit of the NFT pallet
+        #[pallet::weight(T::WeightInfo::set_kitty_uri())]
+        pub fn set_kitty_uri(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            uri: Vec<u8>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(sp_std::str::from_utf8(&uri).is_ok(), Error::<T>::InvalidUtf8);
+
+            orml_nft::Tokens::<T>::try_mutate(kitty_id.0, kitty_id.1, |token| -> DispatchResult {
+                let token = token.as_mut().ok_or(Error::<T>::NotOwner)?;
+                ensure!(token.owner == sender, Error::<T>::NotOwner);
+                token.metadata = uri.clone().try_into().map_err(|_| Error::<T>::UriTooLong)?;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::KittyUriSet(sender, kitty_id, uri));
+
+            Ok(())
+        }
+
+        /// Change the cut of every sale taken by the marketplace
+        #[pallet::weight(T::WeightInfo::set_marketplace_fee())]
+        pub fn set_marketplace_fee(origin: OriginFor<T>, fee: Perbill) -> DispatchResult {
+            ensure_root(origin)?;
+
+            MarketplaceFeeRate::<T>::put(fee);
+
+            Self::deposit_event(Event::MarketplaceFeeSet(fee));
+
+            Ok(())
+        }
+
+        /// Create a collection kitties can be created in from `mint_start` until `mint_end`
+        /// The metadata is stored in the NFT class of the collection
+        #[pallet::weight(T::WeightInfo::create_collection())]
+        pub fn create_collection(
+            origin: OriginFor<T>,
+            metadata: Vec<u8>,
+            max_supply: Option<u32>,
+            mint_start: T::BlockNumber,
+            mint_end: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            T::CollectionOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                mint_end.map_or(true, |end| end > mint_start),
+                Error::<T>::InvalidMintWindow
+            );
+
+            let class_id = orml_nft::Pallet::<T>::create_class(&Self::account_id(), metadata, ())?;
+            Collections::<T>::insert(
+                class_id,
+                Collection {
+                    max_supply,
+                    mint_start,
+                    mint_end,
+                },
+            );
+
+            Self::deposit_event(Event::CollectionCreated(class_id));
+
+            Ok(())
+        }
+
+        /// Approve an account to transfer a kitty with `transfer_from`, None revokes the approval
+        /// The approval is cleared when the kitty changes hands
+        #[pallet::weight(T::WeightInfo::approve())]
+        pub fn approve(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            spender: Option<T::AccountId>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                orml_nft::TokensByOwner::<T>::contains_key(&sender, kitty_id),
+                Error::<T>::NotOwner
+            );
+
+            Approvals::<T>::mutate_exists(kitty_id, |approved| *approved = spender.clone());
+
+            Self::deposit_event(Event::Approved(sender, kitty_id, spender));
+
+            Ok(())
+        }
+
+        /// Allow or disallow an operator to transfer all the kitties of the sender with
+        /// `transfer_from`
+        #[pallet::weight(T::WeightInfo::set_approval_for_all())]
+        pub fn set_approval_for_all(
+            origin: OriginFor<T>,
+            operator: T::AccountId,
+            approved: bool,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            if approved {
+                Operators::<T>::insert(&sender, &operator, ());
+            } else {
+                Operators::<T>::remove(&sender, &operator);
+            }
+
+            Self::deposit_event(Event::ApprovedForAll(sender, operator, approved));
+
+            Ok(())
+        }
+
+        /// Transfer a kitty on behalf of its owner, the sender must be the owner, approved for
+        /// the kitty or an operator of the owner
+        #[pallet::weight(T::WeightInfo::transfer_from(T::MaxOffers::get()))]
+        #[transactional]
+        pub fn transfer_from(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            to: T::AccountId,
+            kitty_id: KittyIdOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                orml_nft::TokensByOwner::<T>::contains_key(&owner, kitty_id),
+                Error::<T>::NotOwner
+            );
+            ensure!(
+                sender == owner
+                    || Self::approvals(kitty_id).as_ref() == Some(&sender)
+                    || Operators::<T>::contains_key(&owner, &sender),
+                Error::<T>::NotApproved
+            );
+
+            Self::do_transfer(&owner, &to, kitty_id)
+        }
+
+        /// Transfer several kitties at once, all or nothing
+        #[pallet::weight(T::WeightInfo::batch_transfer(transfers.len() as u32))]
+        #[transactional]
+        pub fn batch_transfer(
+            origin: OriginFor<T>,
+            transfers: Vec<(T::AccountId, KittyIdOf<T>)>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                transfers.len() as u32 <= T::MaxBatchSize::get(),
+                Error::<T>::BatchTooLarge
+            );
+
+            for (to, kitty_id) in transfers {
+                Self::do_transfer(&sender, &to, kitty_id)?;
+            }
+
+            Ok(())
+        }
+
+        /// Set or remove the price of several kitties at once, all or nothing
+        #[pallet::weight(T::WeightInfo::batch_set_price(prices.len() as u32))]
+        #[transactional]
+        pub fn batch_set_price(
+            origin: OriginFor<T>,
+            prices: Vec<(KittyIdOf<T>, Option<BalanceOf<T>>)>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                prices.len() as u32 <= T::MaxBatchSize::get(),
+                Error::<T>::BatchTooLarge
+            );
+
+            for (kitty_id, new_price) in prices {
+                Self::do_set_price(&sender, kitty_id, new_price)?;
+            }
+
+            Ok(())
+        }
+
+        /// Offer a kitty as a gift, the kitty is locked until `to` accepts or rejects it, the
+        /// sender cancels it or it times out after `TransferOfferTimeout`
+        #[pallet::weight(T::WeightInfo::offer_transfer())]
+        pub fn offer_transfer(
+            origin: OriginFor<T>,
+            to: T::AccountId,
+            kitty_id: KittyIdOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                orml_nft::TokensByOwner::<T>::contains_key(&sender, kitty_id),
+                Error::<T>::NotOwner
+            );
+            Self::ensure_tradable(kitty_id)?;
+
+            let expiry = Self::schedule(
+                frame_system::Pallet::<T>::block_number()
+                    .saturating_add(T::TransferOfferTimeout::get()),
+            );
+            PendingTransfers::<T>::insert(
+                kitty_id,
+                PendingTransfer {
+                    from: sender.clone(),
+                    to: to.clone(),
+                    expiry,
+                },
+            );
+            PendingTransferExpiry::<T>::insert(expiry, kitty_id, ());
+
+            Self::deposit_event(Event::TransferOffered(sender, to, kitty_id, expiry));
+
+            Ok(())
+        }
+
+        /// Accept a kitty offered as a gift
+        #[pallet::weight(T::WeightInfo::accept_transfer(T::MaxOffers::get()))]
+        #[transactional]
+        pub fn accept_transfer(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let pending = Self::take_pending_transfer(kitty_id)?;
+            ensure!(pending.to == sender, Error::<T>::NotRecipient);
+
+            Self::do_transfer(&pending.from, &sender, kitty_id)
+        }
+
+        /// Turn down a kitty offered as a gift, it stays with the sender
+        #[pallet::weight(T::WeightInfo::reject_transfer())]
+        #[transactional]
+        pub fn reject_transfer(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let pending = Self::take_pending_transfer(kitty_id)?;
+            ensure!(pending.to == sender, Error::<T>::NotRecipient);
+
+            Self::deposit_event(Event::TransferRejected(sender, kitty_id));
+
+            Ok(())
+        }
+
+        /// Take back a gift offer before the recipient accepts it
+        #[pallet::weight(T::WeightInfo::cancel_transfer())]
+        #[transactional]
+        pub fn cancel_transfer(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let pending = Self::take_pending_transfer(kitty_id)?;
+            ensure!(pending.from == sender, Error::<T>::NotOwner);
+
+            Self::deposit_event(Event::TransferCancelled(sender, kitty_id));
+
+            Ok(())
+        }
+
+        /// Rent a kitty out on the given terms, None stops renting it out
+        /// A running rental is not affected
+        #[pallet::weight(T::WeightInfo::set_rental_terms())]
+        pub fn set_rental_terms(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            terms: Option<RentalTermsOf<T>>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                orml_nft::TokensByOwner::<T>::contains_key(&sender, kitty_id),
+                Error::<T>::NotOwner
+            );
+            Self::ensure_tradable(kitty_id)?;
+            if let Some(terms) = &terms {
+                ensure!(!terms.max_duration.is_zero(), Error::<T>::InvalidRentalDuration);
+            }
+
+            RentalListings::<T>::mutate_exists(kitty_id, |listing| *listing = terms.clone());
+
+            Self::deposit_event(Event::RentalTermsSet(sender, kitty_id, terms));
+
+            Ok(())
+        }
+
+        /// Rent a kitty for `duration` blocks, paying the owner up front
+        /// The renter can breed the kitty until the rental ends, but can't sell or transfer it
+        #[pallet::weight(T::WeightInfo::rent())]
+        #[transactional]
+        pub fn rent(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            duration: T::BlockNumber,
+            max_price: BalanceOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let owner = orml_nft::Pallet::<T>::tokens(kitty_id.0, kitty_id.1)
+                .ok_or(Error::<T>::InvalidKittyId)?
+                .owner;
+            ensure!(sender != owner, Error::<T>::RentFromSelf);
+            let terms = Self::rental_listings(kitty_id).ok_or(Error::<T>::NotForRent)?;
+            ensure!(
+                !duration.is_zero() && duration <= terms.max_duration,
+                Error::<T>::InvalidRentalDuration
+            );
+            Self::ensure_tradable(kitty_id)?;
+
+            let blocks: u128 = duration.saturated_into();
+            let price = terms.price_per_block.saturating_mul(blocks.saturated_into());
+            ensure!(max_price >= price, Error::<T>::PriceTooLow);
+            let end =
+                Self::schedule(frame_system::Pallet::<T>::block_number().saturating_add(duration));
+            T::Currency::transfer(&sender, &owner, price, ExistenceRequirement::KeepAlive)?;
+
+            // the breeding and battle rights go to the renter
+            SirePrices::<T>::remove(kitty_id);
+            Self::clear_challenges(kitty_id);
+            Rentals::<T>::insert(
+                kitty_id,
+                Rental {
+                    renter: sender.clone(),
+                    end,
+                },
+            );
+            RentalEndTime::<T>::insert(end, kitty_id, ());
+
+            Self::deposit_event(Event::KittyRented(sender, kitty_id, end, price));
+
+            Ok(())
+        }
+
+        /// Lock a kitty in a vault account of the pallet and split it into `total_shares` shares
+        /// held by the sender
+        /// Anyone can buy the kitty out of the vault for `reserve_price`
+        #[pallet::weight(T::WeightInfo::fractionalize(T::MaxOffers::get()))]
+        #[transactional]
+        pub fn fractionalize(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            total_shares: u32,
+            reserve_price: BalanceOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                orml_nft::TokensByOwner::<T>::contains_key(&sender, kitty_id),
+                Error::<T>::NotOwner
+            );
+            Self::ensure_tradable(kitty_id)?;
+            ensure!(total_shares > 0, Error::<T>::InvalidShares);
+
+            let vault_id = NextVaultId::<T>::try_mutate(|id| -> Result<u32, DispatchError> {
+                let current = *id;
+                *id = id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                Ok(current)
+            })?;
+
+            // the vault doesn't count towards the kitties per account
+            let vault_account = Self::vault_account(vault_id);
+            Self::clear_listings(kitty_id);
+            Approvals::<T>::remove(kitty_id);
+            orml_nft::Pallet::<T>::transfer(&sender, &vault_account, kitty_id)?;
+            KittiesOwned::<T>::mutate(&sender, |count| *count = count.saturating_sub(1));
+            Self::move_deposits(kitty_id, &sender, &vault_account)?;
+
+            Vaults::<T>::insert(
+                vault_id,
+                Vault {
+                    kitty_id,
+                    total_shares,
+                    reserve_price,
+                    bought_out: false,
+                },
+            );
+            KittyVaults::<T>::insert(kitty_id, vault_id);
+            Shares::<T>::insert(vault_id, &sender, total_shares);
+
+            Self::deposit_event(Event::KittyFractionalized(
+                sender,
+                kitty_id,
+                vault_id,
+                total_shares,
+            ));
+
+            Ok(())
+        }
+
+        /// Transfer shares of a vault
+        #[pallet::weight(T::WeightInfo::transfer_shares())]
+        #[transactional]
+        pub fn transfer_shares(
+            origin: OriginFor<T>,
+            vault_id: u32,
+            to: T::AccountId,
+            amount: u32,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(Vaults::<T>::contains_key(vault_id), Error::<T>::VaultNotFound);
+            ensure!(amount > 0, Error::<T>::InvalidShares);
+
+            Shares::<T>::try_mutate_exists(vault_id, &sender, |balance| -> DispatchResult {
+                let remaining = balance
+                    .unwrap_or_default()
+                    .checked_sub(amount)
+                    .ok_or(Error::<T>::InsufficientShares)?;
+                *balance = Some(remaining).filter(|remaining| *remaining > 0);
+                Ok(())
+            })?;
+            Shares::<T>::mutate(vault_id, &to, |balance| *balance = balance.saturating_add(amount));
+
+            Self::deposit_event(Event::SharesTransferred(sender, to, vault_id, amount));
+
+            Ok(())
+        }
+
+        /// Take the kitty out of its vault, burning all its shares
+        #[pallet::weight(T::WeightInfo::redeem())]
+        #[transactional]
+        pub fn redeem(origin: OriginFor<T>, vault_id: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let vault = Self::vaults(vault_id).ok_or(Error::<T>::VaultNotFound)?;
+            ensure!(!vault.bought_out, Error::<T>::VaultBoughtOut);
+            ensure!(
+                Self::shares(vault_id, &sender) == vault.total_shares,
+                Error::<T>::InsufficientShares
+            );
+
+            Shares::<T>::remove(vault_id, &sender);
+            Vaults::<T>::remove(vault_id);
+            Self::release_from_vault(vault_id, vault.kitty_id, &sender)?;
+
+            Self::deposit_event(Event::KittyRedeemed(sender, vault_id));
+
+            Ok(())
+        }
+
+        /// Buy the kitty out of its vault, paying the reserve price for the shares held by others
+        /// The marketplace fee and the royalty are taken like on any sale, the other holders
+        /// claim their part of the rest with `claim_buyout`
+        #[pallet::weight(T::WeightInfo::buyout())]
+        #[transactional]
+        pub fn buyout(
+            origin: OriginFor<T>,
+            vault_id: u32,
+            max_price: BalanceOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let mut vault = Self::vaults(vault_id).ok_or(Error::<T>::VaultNotFound)?;
+            ensure!(!vault.bought_out, Error::<T>::VaultBoughtOut);
+
+            // the shares of the buyer are burned for free
+            let own_shares = Shares::<T>::take(vault_id, &sender);
+            let other_shares = vault.total_shares.saturating_sub(own_shares);
+            let price = Self::share_value(vault.reserve_price, other_shares, vault.total_shares);
+            ensure!(max_price >= price, Error::<T>::PriceTooLow);
+
+            let vault_account = Self::vault_account(vault_id);
+            Self::pay_for_kitty(&sender, &vault_account, vault.kitty_id, price, false)?;
+            Self::release_from_vault(vault_id, vault.kitty_id, &sender)?;
+
+            if other_shares == 0 {
+                Vaults::<T>::remove(vault_id);
+            } else {
+                vault.total_shares = other_shares;
+                vault.bought_out = true;
+                Vaults::<T>::insert(vault_id, vault);
+            }
+
+            Self::deposit_event(Event::KittyBoughtOut(sender, vault_id, price));
+
+            Ok(())
+        }
+
+        /// Swap the shares of a bought out vault for their part of the price
+        #[pallet::weight(T::WeightInfo::claim_buyout())]
+        #[transactional]
+        pub fn claim_buyout(origin: OriginFor<T>, vault_id: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let mut vault = Self::vaults(vault_id).ok_or(Error::<T>::VaultNotFound)?;
+            ensure!(vault.bought_out, Error::<T>::NotBoughtOut);
+
+            let shares = Shares::<T>::take(vault_id, &sender);
+            ensure!(shares > 0, Error::<T>::InsufficientShares);
+
+            // what is left in the vault account is shared by the holders yet to claim, so the
+            // last claim empties it
+            let vault_account = Self::vault_account(vault_id);
+            let amount = Self::share_value(
+                T::Currency::free_balance(&vault_account),
+                shares,
+                vault.total_shares,
+            );
+            T::Currency::transfer(
+                &vault_account,
+                &sender,
+                amount,
+                ExistenceRequirement::AllowDeath,
+            )?;
+
+            vault.total_shares = vault.total_shares.saturating_sub(shares);
+            if vault.total_shares == 0 {
+                Vaults::<T>::remove(vault_id);
+            } else {
+                Vaults::<T>::insert(vault_id, vault);
+            }
+
+            Self::deposit_event(Event::BuyoutClaimed(sender, vault_id, amount));
+
+            Ok(())
+        }
+
+        /// Stake a kitty, locking it until unstaked
+        /// The kitty earns `XpPerBlock` and its staker `RewardPerBlock` for every block staked,
+        /// credited lazily when claiming or unstaking
+        #[pallet::weight(T::WeightInfo::stake_kitty())]
+        pub fn stake_kitty(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                orml_nft::TokensByOwner::<T>::contains_key(&sender, kitty_id),
+                Error::<T>::NotOwner
+            );
+            Self::ensure_tradable(kitty_id)?;
+
+            StakedKitties::<T>::insert(
+                kitty_id,
+                Stake {
+                    staker: sender.clone(),
+                    since: frame_system::Pallet::<T>::block_number(),
+                },
+            );
+
+            Self::deposit_event(Event::KittyStaked(sender, kitty_id));
+
+            Ok(())
+        }
+
+        /// Unstake a kitty, crediting its XP and paying the rewards
+        #[pallet::weight(T::WeightInfo::unstake_kitty())]
+        #[transactional]
+        pub fn unstake_kitty(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let mut stake = Self::staked_kitties(kitty_id)
+                .filter(|stake| stake.staker == sender)
+                .ok_or(Error::<T>::NotStaked)?;
+            Self::credit_stake(kitty_id, &mut stake)?;
+            StakedKitties::<T>::remove(kitty_id);
+
+            Self::deposit_event(Event::KittyUnstaked(sender, kitty_id));
+
+            Ok(())
+        }
+
+        /// Credit the XP and pay the rewards of a staked kitty, keeping it staked
+        #[pallet::weight(T::WeightInfo::claim_rewards())]
+        #[transactional]
+        pub fn claim_rewards(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let mut stake = Self::staked_kitties(kitty_id)
+                .filter(|stake| stake.staker == sender)
+                .ok_or(Error::<T>::NotStaked)?;
+            Self::credit_stake(kitty_id, &mut stake)?;
+            StakedKitties::<T>::insert(kitty_id, stake);
+
+            Ok(())
+        }
+
+        /// Top up the pot staking rewards are paid from
+        #[pallet::weight(T::WeightInfo::fund_rewards())]
+        pub fn fund_rewards(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            T::Currency::transfer(
+                &sender,
+                &Self::reward_pot(),
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )?;
+
+            Self::deposit_event(Event::RewardPotFunded(sender, amount));
+
+            Ok(())
+        }
+
+        /// Challenge another kitty to a battle, reserving the stake from the sender
+        /// The challenger needs the right to use the kitty, like its renter. The challenge expires
+        /// after `ChallengeTimeout` unless accepted, rejected or cancelled before
+        #[pallet::weight(T::WeightInfo::challenge())]
+        pub fn challenge(
+            origin: OriginFor<T>,
+            kitty_id: KittyIdOf<T>,
+            opponent_id: KittyIdOf<T>,
+            stake: BalanceOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::usable_kitty(&sender, kitty_id).ok_or(Error::<T>::NotOwner)?;
+            ensure!(kitty_id != opponent_id, Error::<T>::CannotBattleSelf);
+            ensure!(stake >= T::MinChallengeStake::get(), Error::<T>::ChallengeStakeTooLow);
+            ensure!(
+                orml_nft::Pallet::<T>::tokens(opponent_id.0, opponent_id.1).is_some(),
+                Error::<T>::InvalidKittyId
+            );
+            ensure!(!Challenges::<T>::contains_key(kitty_id), Error::<T>::ChallengeExists);
+            ensure!(
+                (ChallengesAgainst::<T>::iter_prefix(opponent_id).count() as u32)
+                    < T::MaxChallenges::get(),
+                Error::<T>::TooManyChallenges
+            );
+            Self::ensure_battle_ready(kitty_id)?;
+
+            T::Currency::reserve(&sender, stake)?;
+            let expiry = Self::schedule(
+                frame_system::Pallet::<T>::block_number()
+                    .saturating_add(T::ChallengeTimeout::get()),
+            );
+            Challenges::<T>::insert(
+                kitty_id,
+                Challenge {
+                    challenger: sender.clone(),
+                    opponent: opponent_id,
+                    stake,
+                    expiry,
+                },
+            );
+            ChallengesAgainst::<T>::insert(opponent_id, kitty_id, ());
+            ChallengeExpiry::<T>::insert(expiry, kitty_id, ());
+
+            Self::deposit_event(Event::ChallengeIssued(
+                sender,
+                kitty_id,
+                opponent_id,
+                stake,
+                expiry,
+            ));
+
+            Ok(())
+        }
+
+        /// Withdraw a challenge, refunding the stake
+        #[pallet::weight(T::WeightInfo::cancel_challenge())]
+        #[transactional]
+        pub fn cancel_challenge(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let challenge = Self::take_challenge(kitty_id)?;
+            ensure!(challenge.challenger == sender, Error::<T>::ChallengeNotFound);
+            T::Currency::unreserve(&sender, challenge.stake);
+
+            Self::deposit_event(Event::ChallengeCancelled(sender, kitty_id));
+
+            Ok(())
+        }
+
+        /// Turn down the challenge of `challenger_id` against a kitty the sender can use,
+        /// refunding the stake of the challenger
+        #[pallet::weight(T::WeightInfo::reject_challenge())]
+        #[transactional]
+        pub fn reject_challenge(
+            origin: OriginFor<T>,
+            challenger_id: KittyIdOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let challenge = Self::take_challenge(challenger_id)?;
+            Self::usable_kitty(&sender, challenge.opponent).ok_or(Error::<T>::NotOwner)?;
+            T::Currency::unreserve(&challenge.challenger, challenge.stake);
+
+            Self::deposit_event(Event::ChallengeRejected(sender, challenger_id));
+
+            Ok(())
+        }
+
+        /// Accept the challenge of `challenger_id` with the challenged kitty, matching the stake
+        /// The battle is fought at the start of the next block with a free slot, with randomness
+        /// the accepter can't know yet. The winner takes both stakes and both kitties rest for
+        /// `BattleCooldown` after the battle
+        #[pallet::weight(T::WeightInfo::accept_challenge())]
+        #[transactional]
+        pub fn accept_challenge(
+            origin: OriginFor<T>,
+            challenger_id: KittyIdOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let challenge = Self::take_challenge(challenger_id)?;
+            let opponent_id = challenge.opponent;
+            Self::usable_kitty(&sender, opponent_id).ok_or(Error::<T>::NotOwner)?;
+            // the challenger may have lost the kitty in the meantime
+            Self::usable_kitty(&challenge.challenger, challenger_id)
+                .ok_or(Error::<T>::InvalidKittyId)?;
+            Self::ensure_battle_ready(challenger_id)?;
+            Self::ensure_battle_ready(opponent_id)?;
+
+            T::Currency::reserve(&sender, challenge.stake)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let fight_at = Self::schedule(now.saturating_add(One::one()));
+            PendingBattles::<T>::insert(
+                fight_at,
+                challenger_id,
+                Battle {
+                    challenger: challenge.challenger,
+                    opponent: opponent_id,
+                    accepter: sender.clone(),
+                    stake: challenge.stake,
+                },
+            );
+            // neither kitty can battle again before this one is over
+            let ready_at = fight_at.saturating_add(T::BattleCooldown::get());
+            BattleReadyAt::<T>::insert(challenger_id, ready_at);
+            BattleReadyAt::<T>::insert(opponent_id, ready_at);
+
+            Self::deposit_event(Event::ChallengeAccepted(
+                sender,
+                challenger_id,
+                opponent_id,
+                fight_at,
+            ));
+
+            Ok(())
+        }
+    }
+}
+
+/// Pays the marketplace fees to [`Pallet::account_id`], creating the account if needed
+/// A fee below the existential deposit of a missing account is burnt instead
+pub struct FeeAccount<T>(PhantomData<T>);
+
+impl<T: Config> OnUnbalanced<NegativeImbalanceOf<T>> for FeeAccount<T> {
+    fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T>) {
+        T::Currency::resolve_creating(&Pallet::<T>::account_id(), amount);
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    fn kitties(owner: &T::AccountId, kitty_id: KittyIdOf<T>) -> Option<Kitty> {
+        orml_nft::Pallet::<T>::tokens(kitty_id.0, kitty_id.1).and_then(|x| {
+            if x.owner == *owner {
+                Some(x.data)
+            } else {
+                None
+            }
+        })
+    }
+
+    // fn get_next_kitty_id() -> Result<KittyIndexOf<T>, DispatchError> {
+    //     NextKittyId::<T>::try_mutate(|current_id_ptr| -> Result<KittyIndexOf<T>, DispatchError> {
+    //         let current_id = *current_id_ptr;
+    //         *current_id_ptr = current_id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+    //         Ok(current_id)
+    //     })
+    // }
+
+    // fn get_next_kitty_id() -> Result<KittyIndexOf<T>, DispatchError> {
+    //     NextKittyId::<T>::try_mutate(|next_id| -> Result<KittyIndexOf<T>, DispatchError> {
+    //         let current_id = *next_id;
+    //         *next_id = next_id
+    //             .checked_add(&One::one())
+    //             .ok_or(ArithmeticError::Overflow)?;
+    //         Ok(current_id)
+    //     })
+    // }
+
+    /// Breed two kitties and mint the child to `owner`, the caller checks the kitties can be used
+    /// The child joins the collection of the first kitty regardless of its mint window
+    fn do_breed(
+        owner: &T::AccountId,
+        kitty_id_1: KittyIdOf<T>,
+        kitty1: &Kitty,
+        kitty_id_2: KittyIdOf<T>,
+        kitty2: &Kitty,
+    ) -> DispatchResult {
+        ensure!(kitty1.gender() != kitty2.gender(), Error::<T>::SameGender);
+
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(
+            Self::ready_to_breed_at(kitty_id_1) <= now
+                && Self::ready_to_breed_at(kitty_id_2) <= now,
+            Error::<T>::KittyOnCooldown
+        );
+        ensure!(!Self::are_related(kitty_id_1, kitty_id_2), Error::<T>::Inbreeding);
+        ensure!(
+            !PendingTransfers::<T>::contains_key(kitty_id_1)
+                && !PendingTransfers::<T>::contains_key(kitty_id_2),
+            Error::<T>::KittyPendingTransfer
+        );
+        ensure!(
+            !StakedKitties::<T>::contains_key(kitty_id_1)
+                && !StakedKitties::<T>::contains_key(kitty_id_2),
+            Error::<T>::KittyStaked
+        );
+
+        // let next_kitty_id: KittyIndexOf<T> = Self::get_next_kitty_id()?;
+
+        let selector = Self::random_value(owner);
+
+        let new_kitty = T::Breeder::breed(kitty1, kitty2, &selector)?;
+
+        // Kitties::<T>::insert(&sender, next_kitty_id, &new_kitty);
+        let kitty_id = Self::mint_kitty(owner, kitty_id_1.0, new_kitty.clone())?;
+
+        let generation =
+            |id: KittyIdOf<T>| Self::kitty_info(id).map_or(0, |info| info.generation);
+        let info = KittyInfo {
+            generation: generation(kitty_id_1)
+                .max(generation(kitty_id_2))
+                .saturating_add(1),
+            parents: Some((kitty_id_1, kitty_id_2)),
+            birth_block: now,
+        };
+        KittyInfos::<T>::insert(kitty_id, &info);
+        Self::reserve_kitty_deposit(owner, kitty_id)?;
+        Royalties::<T>::insert(
+            kitty_id,
+            Royalty {
+                creator: owner.clone(),
+                rate: Perbill::zero(),
+            },
+        );
+
+        // both parents need to rest before breeding again
+        for parent in [kitty_id_1, kitty_id_2].iter() {
+            BreedingStates::<T>::mutate(parent, |state| {
+                state.breed_count = state.breed_count.saturating_add(1);
+                state.ready_at = now.saturating_add(Self::breeding_cooldown(
+                    generation(*parent),
+                    state.breed_count,
+                ));
+            });
+        }
+
+        Self::deposit_event(Event::KittyBred(owner.clone(), kitty_id, new_kitty, info));
+
+        Ok(())
+    }
+
+    /// The kitty if `who` has the right to use it: the renter while it is rented out, the owner
+    /// otherwise
+    fn usable_kitty(who: &T::AccountId, kitty_id: KittyIdOf<T>) -> Option<Kitty> {
+        let token = orml_nft::Pallet::<T>::tokens(kitty_id.0, kitty_id.1)?;
+        let user = Self::rentals(kitty_id).map_or(token.owner, |rental| rental.renter);
+        if user == *who {
+            Some(token.data)
+        } else {
+            None
+        }
+    }
+
+    /// Hand a kitty from its owner to another account
+    fn do_transfer(
+        from: &T::AccountId,
+        to: &T::AccountId,
+        kitty_id: KittyIdOf<T>,
+    ) -> DispatchResult {
+        Self::ensure_tradable(kitty_id)?;
+        orml_nft::Pallet::<T>::transfer(from, to, kitty_id)?;
+
+        // remove the price after transferring
+        if from != to {
+            Self::owner_changed(kitty_id, from, to)?;
+            Self::deposit_event(Event::KittyTransferred(from.clone(), to.clone(), kitty_id));
+        }
+
+        Ok(())
+    }
+
+    /// List a kitty of `owner` for sale, None to delist it
+    fn do_set_price(
+        owner: &T::AccountId,
+        kitty_id: KittyIdOf<T>,
+        new_price: Option<BalanceOf<T>>,
+    ) -> DispatchResult {
+        ensure!(
+            orml_nft::TokensByOwner::<T>::contains_key(owner, kitty_id),
+            Error::<T>::NotOwner
+        );
+        Self::ensure_tradable(kitty_id)?;
+
+        match new_price {
+            Some(_) if !ListingDeposits::<T>::contains_key(kitty_id) => {
+                let deposit = T::ListingDeposit::get();
+                if !deposit.is_zero() {
+                    T::Currency::reserve(owner, deposit)?;
+                    ListingDeposits::<T>::insert(kitty_id, (owner.clone(), deposit));
+                }
+            }
+            Some(_) => {}
+            None => Self::delist(kitty_id),
+        }
+
+        // deletes from the storage if new_price is None
+        KittyPrices::<T>::mutate_exists(kitty_id, |price| *price = new_price);
+
+        Self::deposit_event(Event::KittyPriceUpdated(owner.clone(), kitty_id, new_price));
+
+        Ok(())
+    }
+
+    /// Fails if the kitty can't change hands right now
+    fn ensure_tradable(kitty_id: KittyIdOf<T>) -> DispatchResult {
+        ensure!(
+            !Auctions::<T>::contains_key(kitty_id) && !DutchAuctions::<T>::contains_key(kitty_id),
+            Error::<T>::KittyInAuction
+        );
+        ensure!(
+            !PendingTransfers::<T>::contains_key(kitty_id),
+            Error::<T>::KittyPendingTransfer
+        );
+        ensure!(!Rentals::<T>::contains_key(kitty_id), Error::<T>::KittyRented);
+        ensure!(!StakedKitties::<T>::contains_key(kitty_id), Error::<T>::KittyStaked);
+        Ok(())
+    }
+
+    /// Close the gift offer of a kitty, unlocking it
+    fn take_pending_transfer(
+        kitty_id: KittyIdOf<T>,
+    ) -> Result<PendingTransferOf<T>, DispatchError> {
+        let pending =
+            PendingTransfers::<T>::take(kitty_id).ok_or(Error::<T>::TransferNotFound)?;
+        PendingTransferExpiry::<T>::remove(pending.expiry, kitty_id);
+        Self::unschedule(pending.expiry);
+        Ok(pending)
+    }
+
+    /// Refund and remove the offers for a kitty that expired by `now`, returning how many are left
+    fn prune_expired_offers(kitty_id: KittyIdOf<T>, now: T::BlockNumber) -> u32 {
+        let (expired, left): (Vec<_>, Vec<_>) =
+            Offers::<T>::iter_prefix(kitty_id).partition(|(_, offer)| offer.expiry <= now);
+        for (buyer, offer) in expired {
+            Offers::<T>::remove(kitty_id, &buyer);
+            T::Currency::unreserve(&buyer, offer.amount);
+        }
+        left.len() as u32
+    }
+
+    /// Take one of the `MaxScheduledPerBlock` slots of the first block from `at` on that has a
+    /// free one, returning that block
+    fn schedule(at: T::BlockNumber) -> T::BlockNumber {
+        let max = T::MaxScheduledPerBlock::get().max(1);
+        let mut at = at;
+        while ScheduledCount::<T>::get(at) >= max {
+            at = at.saturating_add(One::one());
+        }
+        ScheduledCount::<T>::mutate(at, |count| *count += 1);
+        at
+    }
+
+    /// Free a slot of the block `at`, for an item removed before it ends
+    fn unschedule(at: T::BlockNumber) {
+        ScheduledCount::<T>::mutate_exists(at, |count| {
+            *count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0);
+        });
+    }
+
+    /// The account receiving the marketplace fees
+    pub fn account_id() -> T::AccountId {
+        T::PalletId::get().into_account()
+    }
+
+    /// Pay the seller for a sold kitty, splitting off the marketplace fee and the royalty of the
+    /// creator
+    /// With `reserved` the price comes out of the reserved balance of the buyer, otherwise out of
+    /// its free balance
+    fn pay_for_kitty(
+        buyer: &T::AccountId,
+        seller: &T::AccountId,
+        kitty_id: KittyIdOf<T>,
+        price: BalanceOf<T>,
+        reserved: bool,
+    ) -> DispatchResult {
+        let pay = |to: &T::AccountId, amount: BalanceOf<T>| -> DispatchResult {
+            if reserved {
+                T::Currency::repatriate_reserved(buyer, to, amount, BalanceStatus::Free)?;
+                Ok(())
+            } else {
+                T::Currency::transfer(buyer, to, amount, ExistenceRequirement::KeepAlive)
+            }
+        };
+
+        let fee = Self::marketplace_fee() * price;
+        if !fee.is_zero() {
+            let imbalance = if reserved {
+                let (imbalance, missing) = T::Currency::slash_reserved(buyer, fee);
+                ensure!(missing.is_zero(), TokenError::NoFunds);
+                imbalance
+            } else {
+                T::Currency::withdraw(
+                    buyer,
+                    fee,
+                    WithdrawReasons::TRANSFER,
+                    ExistenceRequirement::KeepAlive,
+                )?
+            };
+            T::OnMarketplaceFee::on_unbalanced(imbalance);
+            Self::deposit_event(Event::MarketplaceFeePaid(kitty_id, fee));
+        }
+
+        let mut remaining = price.saturating_sub(fee);
+        if let Some(royalty) = Self::royalties(kitty_id) {
+            let amount = (royalty.rate * price).min(remaining);
+            // no royalty on the creator's own trades, nor to a reaped creator it can't revive
+            let receivable = T::Currency::total_balance(&royalty.creator).saturating_add(amount)
+                >= T::Currency::minimum_balance();
+            if !amount.is_zero()
+                && receivable
+                && royalty.creator != *seller
+                && royalty.creator != *buyer
+            {
+                pay(&royalty.creator, amount)?;
+                remaining = remaining.saturating_sub(amount);
+                Self::deposit_event(Event::RoyaltyPaid(royalty.creator, kitty_id, amount));
+            }
+        }
+
+        pay(seller, remaining)
+    }
+
+    /// Clear the listings, offers and approval of a kitty that changed hands and move its
+    /// deposits to the new owner
+    fn owner_changed(
+        kitty_id: KittyIdOf<T>,
+        from: &T::AccountId,
+        to: &T::AccountId,
+    ) -> DispatchResult {
+        Self::clear_listings(kitty_id);
+        Approvals::<T>::remove(kitty_id);
+
+        KittiesOwned::<T>::mutate(from, |count| *count = count.saturating_sub(1));
+        Self::count_new_kitty(to)?;
+
+        Self::move_deposits(kitty_id, from, to)
+    }
+
+    /// Move the deposits reserved for a kitty to its new owner
+    /// A new owner without an account gets one, unless the deposit is below the existential
+    /// deposit, then the move fails
+    fn move_deposits(
+        kitty_id: KittyIdOf<T>,
+        from: &T::AccountId,
+        to: &T::AccountId,
+    ) -> DispatchResult {
+        if let Some(deposit) = Self::kitty_deposits(kitty_id) {
+            Self::move_deposit(from, to, deposit)?;
+        }
+        if let Some(name) = Self::kitty_names(kitty_id) {
+            Self::move_deposit(from, to, name.deposit)?;
+        }
+        Ok(())
+    }
+
+    /// Release a deposit of `from` and reserve it again from `to`
+    fn move_deposit(
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        if amount.is_zero() || from == to {
+            return Ok(());
+        }
+        T::Currency::unreserve(from, amount);
+        T::Currency::transfer(from, to, amount, ExistenceRequirement::AllowDeath)?;
+        T::Currency::reserve(to, amount)
+    }
+
+    /// Fight an accepted challenge, the winner takes both stakes
+    fn fight(challenger_id: KittyIdOf<T>, battle: BattleOf<T>, now: T::BlockNumber) {
+        let dna = |kitty_id: KittyIdOf<T>| {
+            orml_nft::Pallet::<T>::tokens(kitty_id.0, kitty_id.1).map(|token| token.data)
+        };
+        let (challenger, opponent) = match (dna(challenger_id), dna(battle.opponent)) {
+            (Some(challenger), Some(opponent)) => (challenger, opponent),
+            _ => {
+                // a kitty was released since the challenge was accepted, the battle is off
+                T::Currency::unreserve(&battle.challenger, battle.stake);
+                T::Currency::unreserve(&battle.accepter, battle.stake);
+                Self::deposit_event(Event::ChallengeCancelled(battle.challenger, challenger_id));
+                return;
+            }
+        };
+
+        let random = Self::battle_random(challenger_id);
+        let (winner, loser, winner_id, loser_id) =
+            match battle::resolve_battle(&challenger, &opponent, &random) {
+                battle::Winner::Challenger => {
+                    (&battle.challenger, &battle.accepter, challenger_id, battle.opponent)
+                }
+                battle::Winner::Opponent => {
+                    (&battle.accepter, &battle.challenger, battle.opponent, challenger_id)
+                }
+            };
+        T::Currency::unreserve(winner, battle.stake);
+        // the winner's account exists, it just got its stake back
+        let _ = T::Currency::repatriate_reserved(loser, winner, battle.stake, BalanceStatus::Free);
+
+        for (kitty_id, opponent, won) in
+            [(winner_id, loser_id, true), (loser_id, winner_id, false)].iter()
+        {
+            BattleHistory::<T>::mutate(kitty_id, |history| {
+                // only the latest battles are kept
+                if !history.is_empty() && history.len() >= T::MaxBattleHistory::get() as usize {
+                    history.remove(0);
+                }
+                // only fails if no history is kept at all
+                let _ = history.try_push(BattleRecord {
+                    opponent: *opponent,
+                    won: *won,
+                    block: now,
+                });
+            });
+        }
+
+        Self::deposit_event(Event::BattleFought(winner.clone(), winner_id, loser_id, battle.stake));
+    }
+
+    /// Randomness of the current block for the battle of `challenger_id`, only known once the
+    /// block that accepted the challenge is sealed
+    fn battle_random(challenger_id: KittyIdOf<T>) -> [u8; 16] {
+        let subject = (b"kitties/battle", challenger_id).encode();
+        T::Randomness::random(&subject).0.using_encoded(blake2_128)
+    }
+
+    /// Fails if the kitty is still resting from its last battle
+    fn ensure_battle_ready(kitty_id: KittyIdOf<T>) -> DispatchResult {
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(Self::battle_ready_at(kitty_id) <= now, Error::<T>::KittyOnBattleCooldown);
+        Ok(())
+    }
+
+    /// The account staking rewards are paid from
+    pub fn reward_pot() -> T::AccountId {
+        T::PalletId::get().into_sub_account(b"rewards")
+    }
+
+    /// Credit the XP a staked kitty earned since the last credit and pay the staker its rewards,
+    /// as far as the reward pot allows
+    fn credit_stake(kitty_id: KittyIdOf<T>, stake: &mut StakeOf<T>) -> DispatchResult {
+        let now = frame_system::Pallet::<T>::block_number();
+        let blocks: u64 = now.saturating_sub(stake.since).saturated_into();
+        stake.since = now;
+
+        let xp = blocks.saturating_mul(T::XpPerBlock::get());
+        KittyXp::<T>::mutate(kitty_id, |total| *total = total.saturating_add(xp));
+
+        let pot = Self::reward_pot();
+        let available =
+            T::Currency::free_balance(&pot).saturating_sub(T::Currency::minimum_balance());
+        let reward = T::RewardPerBlock::get()
+            .saturating_mul(blocks.saturated_into())
+            .min(available);
+        T::Currency::transfer(&pot, &stake.staker, reward, ExistenceRequirement::KeepAlive)?;
+
+        Self::deposit_event(Event::StakingRewarded(stake.staker.clone(), kitty_id, xp, reward));
+
+        Ok(())
+    }
+
+    /// The account holding a fractionalized kitty, its deposits and its buyout proceeds
+    pub fn vault_account(vault_id: u32) -> T::AccountId {
+        T::PalletId::get().into_sub_account((b"vault", vault_id))
+    }
+
+    /// Hand a kitty out of its vault
+    fn release_from_vault(
+        vault_id: u32,
+        kitty_id: KittyIdOf<T>,
+        to: &T::AccountId,
+    ) -> DispatchResult {
+        let vault_account = Self::vault_account(vault_id);
+        KittyVaults::<T>::remove(kitty_id);
+        orml_nft::Pallet::<T>::transfer(&vault_account, to, kitty_id)?;
+        Self::count_new_kitty(to)?;
+        Self::move_deposits(kitty_id, &vault_account, to)
+    }
+
+    /// Part of `amount` that `shares` out of `total_shares` are worth
+    fn share_value(amount: BalanceOf<T>, shares: u32, total_shares: u32) -> BalanceOf<T> {
+        let amount: u128 = amount.saturated_into();
+        let value = amount.saturating_mul(shares.into()) / u128::from(total_shares);
+        value.saturated_into()
+    }
+
+    /// Take a kitty off the market, refunding the listing deposit, the offers and the stakes of
+    /// the open challenges by and against it
+    fn clear_listings(kitty_id: KittyIdOf<T>) {
+        Self::delist(kitty_id);
+        SirePrices::<T>::remove(kitty_id);
+        RentalListings::<T>::remove(kitty_id);
+        for (buyer, offer) in Offers::<T>::drain_prefix(kitty_id) {
+            T::Currency::unreserve(&buyer, offer.amount);
+        }
+        Self::clear_challenges(kitty_id);
+    }
+
+    /// Withdraw the open challenges by and against a kitty whose user changes, refunding the
+    /// stakes
+    fn clear_challenges(kitty_id: KittyIdOf<T>) {
+        let challenger_ids = ChallengesAgainst::<T>::drain_prefix(kitty_id)
+            .map(|(challenger_id, _)| challenger_id)
+            .collect::<Vec<_>>();
+        for challenger_id in challenger_ids.into_iter().chain(sp_std::iter::once(kitty_id)) {
+            if let Ok(challenge) = Self::take_challenge(challenger_id) {
+                T::Currency::unreserve(&challenge.challenger, challenge.stake);
+            }
+        }
+    }
+
+    /// Close an open challenge, the caller refunds the stake
+    fn take_challenge(challenger_id: KittyIdOf<T>) -> Result<ChallengeOf<T>, DispatchError> {
+        let challenge =
+            Challenges::<T>::take(challenger_id).ok_or(Error::<T>::ChallengeNotFound)?;
+        ChallengesAgainst::<T>::remove(challenge.opponent, challenger_id);
+        ChallengeExpiry::<T>::remove(challenge.expiry, challenger_id);
+        Self::unschedule(challenge.expiry);
+        Ok(challenge)
+    }
+
+    /// Mint a kitty into a collection within the supply limits
+    fn mint_kitty(
+        owner: &T::AccountId,
+        collection: ClassIdOf<T>,
+        kitty: Kitty,
+    ) -> Result<KittyIdOf<T>, DispatchError> {
+        let info = Self::collections(collection).ok_or(Error::<T>::CollectionNotFound)?;
+        if let Some(max) = info.max_supply {
+            let supply = orml_nft::Pallet::<T>::classes(collection)
+                .map_or_else(Zero::zero, |class| class.total_issuance);
+            ensure!(supply < max.into(), Error::<T>::CollectionSupplyReached);
+        }
+        TotalKitties::<T>::try_mutate(|total| -> DispatchResult {
+            if let Some(max) = T::MaxTotalKitties::get() {
+                ensure!(*total < max, Error::<T>::MaxTotalKittiesReached);
+            }
+            *total = total.saturating_add(1);
+            Ok(())
+        })?;
+        Self::count_new_kitty(owner)?;
+
+        let token_id = orml_nft::Pallet::<T>::mint(owner, collection, Vec::new(), kitty)?;
+        Ok((collection, token_id))
+    }
+
+    /// Count a kitty the account receives, within the per account limit
+    fn count_new_kitty(owner: &T::AccountId) -> DispatchResult {
+        KittiesOwned::<T>::try_mutate(owner, |count| -> DispatchResult {
+            ensure!(*count < T::MaxKittiesPerAccount::get(), Error::<T>::TooManyKittiesOwned);
+            *count += 1;
+            Ok(())
+        })
+    }
+
+    /// Reserve the deposit of a new kitty from its owner
+    fn reserve_kitty_deposit(owner: &T::AccountId, kitty_id: KittyIdOf<T>) -> DispatchResult {
+        let deposit = T::KittyDeposit::get();
+        if !deposit.is_zero() {
+            T::Currency::reserve(owner, deposit)?;
+            KittyDeposits::<T>::insert(kitty_id, deposit);
+        }
+        Ok(())
+    }
+
+    /// Take a kitty off the fixed price sale, refunding the listing deposit
+    fn delist(kitty_id: KittyIdOf<T>) {
+        KittyPrices::<T>::remove(kitty_id);
+        if let Some((depositor, deposit)) = ListingDeposits::<T>::take(kitty_id) {
+            T::Currency::unreserve(&depositor, deposit);
+        }
+    }
+
+    /// Price of a kitty in a Dutch auction at the current block, None if not auctioned
+    pub fn current_dutch_price(kitty_id: KittyIdOf<T>) -> Option<BalanceOf<T>> {
+        let now = frame_system::Pallet::<T>::block_number();
+        Self::dutch_auctions(kitty_id).map(|auction| Self::dutch_price(&auction, now))
+    }
+
+    /// Price of a Dutch auction at block `now`
+    fn dutch_price(auction: &DutchAuctionOf<T>, now: T::BlockNumber) -> BalanceOf<T> {
+        let elapsed: u128 = now.saturating_sub(auction.start).saturated_into();
+        let duration: u128 = auction.duration.saturated_into();
+        if elapsed >= duration {
+            return auction.floor_price;
+        }
+
+        let start_price: u128 = auction.start_price.saturated_into();
+        let floor_price: u128 = auction.floor_price.saturated_into();
+        let discount = start_price.saturating_sub(floor_price).saturating_mul(elapsed) / duration;
+
+        start_price.saturating_sub(discount).saturated_into()
+    }
+
+    /// Hand the kitty to the highest bidder and pay the seller, called at the end of the auction
+    fn settle_auction(kitty_id: KittyIdOf<T>, auction: AuctionOf<T>) {
+        let (winner, price) = match auction.best_bid {
+            Some(best_bid) => best_bid,
+            None => {
+                Self::deposit_event(Event::AuctionClosed(auction.seller, kitty_id));
+                return;
+            }
+        };
+
+        if Self::sell_to_winner(&auction.seller, &winner, kitty_id, price).is_err() {
+            // the kitty is gone, give the winner its money back
+            T::Currency::unreserve(&winner, price);
+            Self::deposit_event(Event::AuctionClosed(auction.seller, kitty_id));
+            return;
+        }
+
+        Self::deposit_event(Event::AuctionSettled(auction.seller, winner, kitty_id, price));
+    }
+
+    /// Hand an auctioned kitty to the winner and pay the seller, all or nothing
+    #[transactional]
+    fn sell_to_winner(
+        seller: &T::AccountId,
+        winner: &T::AccountId,
+        kitty_id: KittyIdOf<T>,
+        price: BalanceOf<T>,
+    ) -> DispatchResult {
+        orml_nft::Pallet::<T>::transfer(seller, winner, kitty_id)?;
+        Self::pay_for_kitty(winner, seller, kitty_id, price, true)?;
+        Self::owner_changed(kitty_id, seller, winner)
+    }
+
+    /// The first block the kitty can breed at
+    pub fn ready_to_breed_at(kitty_id: KittyIdOf<T>) -> T::BlockNumber {
+        Self::breeding_state(kitty_id).ready_at
+    }
+
+    /// Cooldown of a kitty of the given generation after its `breed_count`-th breeding
+    pub fn breeding_cooldown(generation: u32, breed_count: u32) -> T::BlockNumber {
+        let steps = generation.saturating_add(breed_count);
+        T::BaseBreedingCooldown::get()
+            .saturating_mul(steps.into())
+            .min(T::MaxBreedingCooldown::get())
+    }
+
+    /// Ancestors of a kitty up to `depth` generations back
+    pub fn ancestors(kitty_id: KittyIdOf<T>, depth: u32) -> BTreeSet<KittyIdOf<T>> {
+        let mut ancestors = BTreeSet::new();
+        let mut generation = sp_std::vec![kitty_id];
+        for _ in 0..depth {
+            let mut parents = Vec::new();
+            for id in generation {
+                if let Some((parent1, parent2)) = Self::kitty_info(id).and_then(|info| info.parents) {
+                    parents.push(parent1);
+                    parents.push(parent2);
+                }
+            }
+            ancestors.extend(parents.iter().cloned());
+            generation = parents;
+        }
+        ancestors
+    }
+
+    /// Whether one kitty is the ancestor of the other or they share an ancestor, within
+    /// `InbreedingDepth` generations
+    pub fn are_related(kitty_id_1: KittyIdOf<T>, kitty_id_2: KittyIdOf<T>) -> bool {
+        let depth = T::InbreedingDepth::get();
+        let ancestors1 = Self::ancestors(kitty_id_1, depth);
+        let ancestors2 = Self::ancestors(kitty_id_2, depth);
+
+        ancestors1.contains(&kitty_id_2)
+            || ancestors2.contains(&kitty_id_1)
+            || !ancestors1.is_disjoint(&ancestors2)
+    }
+
+    /// Worst case number of kitty info reads of `are_related`
+    fn pedigree_reads() -> Weight {
+        // every kitty reads its own info and the info of its ancestors but the oldest generation
+        let per_kitty = 2u64.saturating_pow(T::InbreedingDepth::get()).saturating_sub(1);
+        per_kitty.saturating_mul(2)
+    }
 
     /// Generate a random 128bit value
     fn random_value(sender: &T::AccountId) -> [u8; 16] {
@@ -0,0 +1,183 @@
+//! Storage migrations for the kitties pallet.
+
+use super::*;
+use codec::FullCodec;
+use frame_support::{
+    storage::{self, migration},
+    traits::PalletInfoAccess,
+    StorageHasher,
+};
+use sp_runtime::traits::Zero;
+
+/// Records the kitties minted before `KittyInfos` existed as generation 0 kitties without parents.
+/// Their birth block is unknown and is recorded as block 0.
+pub fn migrate_to_v2<T: Config>() -> Weight {
+    if StorageVersion::<T>::get() != Releases::V1 {
+        return T::DbWeight::get().reads(1);
+    }
+
+    let module = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+    let mut migrated: Weight = 0;
+    for (kitty_id, _) in orml_nft::Tokens::<T>::iter_prefix(Pallet::<T>::class_id()) {
+        // kitty info is keyed by the token id alone until V4
+        let key = Blake2_128Concat::hash(&kitty_id.encode());
+        if !migration::have_storage_value(module, b"KittyInfos", &key) {
+            migration::put_storage_value(
+                module,
+                b"KittyInfos",
+                &key,
+                KittyInfo::<KittyIndexOf<T>, T::BlockNumber> {
+                    generation: 0,
+                    parents: None,
+                    birth_block: Zero::zero(),
+                },
+            );
+        }
+        migrated += 1;
+    }
+
+    StorageVersion::<T>::put(Releases::V2);
+
+    // the version, the class id and every kitty are read, every kitty info and the version written
+    T::DbWeight::get().reads_writes(2 + 2 * migrated, 1 + migrated)
+}
+
+/// Counts the kitties of every account into `KittiesOwned`.
+pub fn migrate_to_v3<T: Config>() -> Weight {
+    if StorageVersion::<T>::get() != Releases::V2 {
+        return T::DbWeight::get().reads(1);
+    }
+
+    let mut counted: Weight = 0;
+    for (_, token) in orml_nft::Tokens::<T>::iter_prefix(Pallet::<T>::class_id()) {
+        KittiesOwned::<T>::mutate(&token.owner, |count| *count = count.saturating_add(1));
+        counted += 1;
+    }
+
+    StorageVersion::<T>::put(Releases::V3);
+
+    // the version, the class id and every kitty are read, every count read and written
+    T::DbWeight::get().reads_writes(2 + 2 * counted, 1 + counted)
+}
+
+/// Keys every kitty by collection and token id, the kitties minted so far belong to the
+/// collection created at genesis.
+/// A chain without that class gets a new one, so kitties can be minted again.
+pub fn migrate_to_v4<T: Config>() -> Weight {
+    if StorageVersion::<T>::get() != Releases::V3 {
+        return T::DbWeight::get().reads(1);
+    }
+
+    let mut weight = T::DbWeight::get().reads_writes(3, 3);
+    if !orml_nft::Classes::<T>::contains_key(Pallet::<T>::class_id()) {
+        match orml_nft::Pallet::<T>::create_class(&Pallet::<T>::account_id(), Vec::new(), ()) {
+            Ok(class_id) => ClassId::<T>::put(class_id),
+            Err(e) => frame_support::log::warn!(
+                target: "runtime::kitties",
+                "no kitty class and none can be created: {:?}",
+                e
+            ),
+        }
+        weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 3));
+    }
+
+    let class_id = Pallet::<T>::class_id();
+    let module = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+
+    weight = weight
+        .saturating_add(rekey::<T, _, KittyPrices<T>>(b"KittyPrices"))
+        .saturating_add(rekey::<T, _, SirePrices<T>>(b"SirePrices"))
+        .saturating_add(rekey::<T, _, BreedingStates<T>>(b"BreedingStates"))
+        .saturating_add(rekey::<T, _, Auctions<T>>(b"Auctions"))
+        .saturating_add(rekey::<T, _, DutchAuctions<T>>(b"DutchAuctions"))
+        .saturating_add(rekey::<T, _, KittyDeposits<T>>(b"KittyDeposits"))
+        .saturating_add(rekey::<T, _, ListingDeposits<T>>(b"ListingDeposits"))
+        .saturating_add(rekey::<T, _, KittyNames<T>>(b"KittyNames"))
+        .saturating_add(rekey::<T, _, Royalties<T>>(b"Royalties"));
+
+    // the parents are kitty ids as well
+    let infos = migration::storage_key_iter::<
+        KittyIndexOf<T>,
+        KittyInfo<KittyIndexOf<T>, T::BlockNumber>,
+        Blake2_128Concat,
+    >(module, b"KittyInfos")
+    .drain()
+    .collect::<Vec<_>>();
+    for (kitty_id, info) in infos.iter() {
+        KittyInfos::<T>::insert(
+            (class_id, *kitty_id),
+            KittyInfo {
+                generation: info.generation,
+                parents: info
+                    .parents
+                    .map(|(parent1, parent2)| ((class_id, parent1), (class_id, parent2))),
+                birth_block: info.birth_block,
+            },
+        );
+    }
+    weight = weight.saturating_add(T::DbWeight::get().reads_writes(
+        infos.len() as Weight,
+        2 * infos.len() as Weight,
+    ));
+
+    // the auctions to settle are rebuilt from the re-keyed auctions
+    migration::remove_storage_prefix(module, b"AuctionEndTime", &[]);
+    for (kitty_id, auction) in Auctions::<T>::iter() {
+        AuctionEndTime::<T>::insert(auction.end, kitty_id, ());
+        weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+    }
+
+    // the raw key of an offer is the hashed kitty index followed by the hashed buyer
+    let offers = migration::storage_iter::<OfferOf<T>>(module, b"Offers")
+        .drain()
+        .collect::<Vec<_>>();
+    for (key, offer) in offers.iter() {
+        let mut input = key.get(16..).unwrap_or_default();
+        let kitty_id = KittyIndexOf::<T>::decode(&mut input);
+        let buyer = input.get(16..).and_then(|mut input| T::AccountId::decode(&mut input).ok());
+        if let (Ok(kitty_id), Some(buyer)) = (kitty_id, buyer) {
+            Offers::<T>::insert((class_id, kitty_id), buyer, offer);
+        }
+    }
+    weight = weight.saturating_add(T::DbWeight::get().reads_writes(
+        offers.len() as Weight,
+        2 * offers.len() as Weight,
+    ));
+
+    if let Some(class) = orml_nft::Pallet::<T>::classes(class_id) {
+        Collections::<T>::insert(
+            class_id,
+            Collection {
+                max_supply: None,
+                mint_start: Zero::zero(),
+                mint_end: None,
+            },
+        );
+        TotalKitties::<T>::put(class.total_issuance.saturated_into::<u32>());
+    }
+
+    StorageVersion::<T>::put(Releases::V4);
+
+    weight
+}
+
+/// Moves the entries of a map keyed by kitty index to the same index in the genesis collection.
+fn rekey<T, V, M>(item: &[u8]) -> Weight
+where
+    T: Config,
+    V: FullCodec,
+    M: storage::StorageMap<KittyIdOf<T>, V>,
+{
+    let class_id = Pallet::<T>::class_id();
+    let module = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+    let entries = migration::storage_key_iter::<KittyIndexOf<T>, V, Blake2_128Concat>(module, item)
+        .drain()
+        .collect::<Vec<_>>();
+    let count = entries.len() as Weight;
+    for (kitty_id, value) in entries {
+        M::insert((class_id, kitty_id), value);
+    }
+
+    // every entry is read, removed and written back
+    T::DbWeight::get().reads_writes(count, 2 * count)
+}
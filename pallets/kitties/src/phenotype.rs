@@ -0,0 +1,257 @@
+//! Decoding of the visible traits of a kitty from its 16 byte DNA.
+//!
+//! Every trait is read from the expressed (dominant) allele of its own gene, see
+//! [`genetics::Gene`]:
+//!
+//! | gene   | trait                              |
+//! |--------|------------------------------------|
+//! | 0      | gender (see [`Kitty::gender`])     |
+//! | 1      | body colour                        |
+//! | 2      | eye colour                         |
+//! | 3      | pattern                            |
+//! | 4      | pattern colour                     |
+//! | 5      | ear shape                          |
+//! | 6      | tail                               |
+//! | 7      | fur length                         |
+//! | 8..12  | accessory slots                    |
+//! | 12..16 | reserved                           |
+//!
+//! Gender is not subject to dominance and is read from the lowest bit of its gene. An accessory
+//! slot looks at both alleles, see [`Accessory::from_gene`]. Any byte value decodes to a valid
+//! variant, so every DNA has a phenotype.
+
+use super::*;
+use genetics::Gene;
+
+/// Gene index of each decoded trait.
+pub const GENDER_GENE: usize = 0;
+pub const BODY_COLOUR_GENE: usize = 1;
+pub const EYE_COLOUR_GENE: usize = 2;
+pub const PATTERN_GENE: usize = 3;
+pub const PATTERN_COLOUR_GENE: usize = 4;
+pub const EAR_SHAPE_GENE: usize = 5;
+pub const TAIL_GENE: usize = 6;
+pub const FUR_GENE: usize = 7;
+pub const ACCESSORY_GENES: [usize; ACCESSORY_SLOTS] = [8, 9, 10, 11];
+
+/// Number of accessories a kitty can wear at once.
+pub const ACCESSORY_SLOTS: usize = 4;
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone, Copy)]
+pub enum Colour {
+    Black,
+    White,
+    Ginger,
+    Grey,
+    Cream,
+    Chocolate,
+    Lilac,
+    Cinnamon,
+}
+
+impl Colour {
+    pub const VARIANTS: u8 = 8;
+
+    pub fn from_gene(gene: u8) -> Self {
+        match gene % Self::VARIANTS {
+            0 => Colour::Black,
+            1 => Colour::White,
+            2 => Colour::Ginger,
+            3 => Colour::Grey,
+            4 => Colour::Cream,
+            5 => Colour::Chocolate,
+            6 => Colour::Lilac,
+            _ => Colour::Cinnamon,
+        }
+    }
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone, Copy)]
+pub enum EyeColour {
+    Green,
+    Blue,
+    Amber,
+    Copper,
+    Hazel,
+    /// Each eye a different colour.
+    OddEyed,
+}
+
+impl EyeColour {
+    pub const VARIANTS: u8 = 6;
+
+    pub fn from_gene(gene: u8) -> Self {
+        match gene % Self::VARIANTS {
+            0 => EyeColour::Green,
+            1 => EyeColour::Blue,
+            2 => EyeColour::Amber,
+            3 => EyeColour::Copper,
+            4 => EyeColour::Hazel,
+            _ => EyeColour::OddEyed,
+        }
+    }
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone, Copy)]
+pub enum Pattern {
+    Solid,
+    Tabby,
+    Spotted,
+    Tortoiseshell,
+    Calico,
+    Pointed,
+}
+
+impl Pattern {
+    pub const VARIANTS: u8 = 6;
+
+    pub fn from_gene(gene: u8) -> Self {
+        match gene % Self::VARIANTS {
+            0 => Pattern::Solid,
+            1 => Pattern::Tabby,
+            2 => Pattern::Spotted,
+            3 => Pattern::Tortoiseshell,
+            4 => Pattern::Calico,
+            _ => Pattern::Pointed,
+        }
+    }
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone, Copy)]
+pub enum EarShape {
+    Pointy,
+    Rounded,
+    Folded,
+    Curled,
+    Tufted,
+}
+
+impl EarShape {
+    pub const VARIANTS: u8 = 5;
+
+    pub fn from_gene(gene: u8) -> Self {
+        match gene % Self::VARIANTS {
+            0 => EarShape::Pointy,
+            1 => EarShape::Rounded,
+            2 => EarShape::Folded,
+            3 => EarShape::Curled,
+            _ => EarShape::Tufted,
+        }
+    }
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone, Copy)]
+pub enum Tail {
+    Long,
+    Short,
+    Fluffy,
+    Bobbed,
+}
+
+impl Tail {
+    pub const VARIANTS: u8 = 4;
+
+    pub fn from_gene(gene: u8) -> Self {
+        match gene % Self::VARIANTS {
+            0 => Tail::Long,
+            1 => Tail::Short,
+            2 => Tail::Fluffy,
+            _ => Tail::Bobbed,
+        }
+    }
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone, Copy)]
+pub enum FurLength {
+    Hairless,
+    Short,
+    Medium,
+    Long,
+}
+
+impl FurLength {
+    pub const VARIANTS: u8 = 4;
+
+    pub fn from_gene(gene: u8) -> Self {
+        match gene % Self::VARIANTS {
+            0 => FurLength::Hairless,
+            1 => FurLength::Short,
+            2 => FurLength::Medium,
+            _ => FurLength::Long,
+        }
+    }
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone, Copy)]
+pub enum Accessory {
+    Hat,
+    Bowtie,
+    Glasses,
+    Collar,
+    Scarf,
+    Crown,
+}
+
+impl Accessory {
+    pub const VARIANTS: u8 = 6;
+
+    /// Accessories are rare: a slot is only filled when its expressed allele is zero, which is 31
+    /// of the 256 gene values (about 12%). The recessive allele then picks the accessory.
+    pub fn from_gene(gene: Gene) -> Option<Self> {
+        if gene.expressed() != 0 {
+            return None;
+        }
+
+        match gene.recessive() % Self::VARIANTS {
+            0 => Some(Accessory::Hat),
+            1 => Some(Accessory::Bowtie),
+            2 => Some(Accessory::Glasses),
+            3 => Some(Accessory::Collar),
+            4 => Some(Accessory::Scarf),
+            _ => Some(Accessory::Crown),
+        }
+    }
+}
+
+/// The visible traits of a kitty.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct Phenotype {
+    pub gender: KittyGender,
+    pub body_colour: Colour,
+    pub eye_colour: EyeColour,
+    pub pattern: Pattern,
+    pub pattern_colour: Colour,
+    pub ear_shape: EarShape,
+    pub tail: Tail,
+    pub fur: FurLength,
+    pub accessories: [Option<Accessory>; ACCESSORY_SLOTS],
+}
+
+impl Phenotype {
+    pub fn from_dna(dna: &[u8; 16]) -> Self {
+        let mut accessories = [None; ACCESSORY_SLOTS];
+        for (slot, gene) in ACCESSORY_GENES.iter().enumerate() {
+            accessories[slot] = Accessory::from_gene(Gene(dna[*gene]));
+        }
+
+        Phenotype {
+            gender: Kitty(*dna).gender(),
+            body_colour: Colour::from_gene(Gene(dna[BODY_COLOUR_GENE]).expressed()),
+            eye_colour: EyeColour::from_gene(Gene(dna[EYE_COLOUR_GENE]).expressed()),
+            pattern: Pattern::from_gene(Gene(dna[PATTERN_GENE]).expressed()),
+            pattern_colour: Colour::from_gene(Gene(dna[PATTERN_COLOUR_GENE]).expressed()),
+            ear_shape: EarShape::from_gene(Gene(dna[EAR_SHAPE_GENE]).expressed()),
+            tail: Tail::from_gene(Gene(dna[TAIL_GENE]).expressed()),
+            fur: FurLength::from_gene(Gene(dna[FUR_GENE]).expressed()),
+            accessories,
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use super::*;
 
 use crate as kitties;
-use frame_support::{assert_noop, assert_ok, parameter_types};
+use frame_support::{assert_noop, assert_ok, parameter_types, StorageHasher};
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
+    Perbill,
 };
 
 // construct mocked test runtime
@@ -23,6 +24,7 @@ frame_support::construct_runtime!(
         // RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage},
         Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         KittiesModule: kitties::{Pallet, Call, Storage, Event<T>},
+        Nft: orml_nft::{Pallet, Storage, Config<T>},
     }
 );
 
@@ -76,7 +78,7 @@ impl Randomness<H256, u64> for MockRandom {
 
 // conf balances spallet
 parameter_types! {
-    pub const ExistentialDeposit: u64 = 1;
+    pub static ExistentialDeposit: u64 = 1;
 }
 
 impl pallet_balances::Config for Test {
@@ -92,19 +94,118 @@ impl pallet_balances::Config for Test {
 }
 
 // configure kitties pallet
+parameter_types! {
+    pub static MockBreederFails: bool = false;
+    pub const BaseBreedingCooldown: u64 = 10;
+    pub const MaxBreedingCooldown: u64 = 35;
+    pub static InbreedingDepth: u32 = 1;
+    pub const AuctionExtension: u64 = 5;
+    pub const MaxAuctionDuration: u64 = 100;
+    pub static MaxKittiesPerAccount: u32 = 100;
+    pub static MaxTotalKitties: Option<u32> = None;
+    pub static KittyDeposit: u64 = 0;
+    pub static ListingDeposit: u64 = 0;
+    pub const MaxNameLength: u32 = 16;
+    pub static NameDeposit: u64 = 0;
+    pub const MaxRoyalty: Perbill = Perbill::from_percent(10);
+    pub const MarketplaceFee: Perbill = Perbill::zero();
+    pub const KittiesPalletId: PalletId = PalletId(*b"py/kitty");
+    pub const MaxOffers: u32 = 3;
+    pub const MaxOfferDuration: u64 = 50;
+    pub const MaxBatchSize: u32 = 3;
+    pub const TransferOfferTimeout: u64 = 10;
+    pub const XpPerBlock: u64 = 3;
+    pub const RewardPerBlock: u64 = 2;
+    pub const BattleCooldown: u64 = 5;
+    pub const MinChallengeStake: u64 = 5;
+    pub const ChallengeTimeout: u64 = 20;
+    pub const MaxChallenges: u32 = 2;
+    pub const MaxBattleHistory: u32 = 2;
+    pub static MaxScheduledPerBlock: u32 = 10;
+}
+
+// deterministic breeder: first half of the DNA from the first parent, second half from the other
+pub struct MockBreeder;
+
+impl Breeder for MockBreeder {
+    fn breed(
+        parent1: &Kitty,
+        parent2: &Kitty,
+        _random: &[u8; 16],
+    ) -> Result<Kitty, DispatchError> {
+        ensure!(!MockBreederFails::get(), "breeding failed");
+
+        let mut dna = parent1.0;
+        dna[8..].copy_from_slice(&parent2.0[8..]);
+        Ok(Kitty(dna))
+    }
+}
+
 impl Config for Test {
     type Event = Event;
     type Randomness = MockRandom;
-    type KittyIndex = u32;
     type Currency = Balances;
+    type Breeder = MockBreeder;
+    type BaseBreedingCooldown = BaseBreedingCooldown;
+    type MaxBreedingCooldown = MaxBreedingCooldown;
+    type InbreedingDepth = InbreedingDepth;
+    type AuctionExtension = AuctionExtension;
+    type MaxAuctionDuration = MaxAuctionDuration;
+    type MaxKittiesPerAccount = MaxKittiesPerAccount;
+    type CollectionOrigin = frame_system::EnsureRoot<u64>;
+    type MaxTotalKitties = MaxTotalKitties;
+    type KittyDeposit = KittyDeposit;
+    type ListingDeposit = ListingDeposit;
+    type MaxNameLength = MaxNameLength;
+    type NameDeposit = NameDeposit;
+    type MaxRoyalty = MaxRoyalty;
+    type MarketplaceFee = MarketplaceFee;
+    type PalletId = KittiesPalletId;
+    type OnMarketplaceFee = FeeAccount<Test>;
+    type MaxOffers = MaxOffers;
+    type MaxOfferDuration = MaxOfferDuration;
+    type MaxBatchSize = MaxBatchSize;
+    type TransferOfferTimeout = TransferOfferTimeout;
+    type XpPerBlock = XpPerBlock;
+    type RewardPerBlock = RewardPerBlock;
+    type BattleCooldown = BattleCooldown;
+    type MinChallengeStake = MinChallengeStake;
+    type ChallengeTimeout = ChallengeTimeout;
+    type MaxChallenges = MaxChallenges;
+    type MaxBattleHistory = MaxBattleHistory;
+    type MaxScheduledPerBlock = MaxScheduledPerBlock;
+    type WeightInfo = ();
+}
+
+// configure NFT pallet
+parameter_types! {
+    pub const MaxClassMetadata: u32 = 32;
+    pub const MaxTokenMetadata: u32 = 32;
+}
+
+impl orml_nft::Config for Test {
+    type ClassId = u32;
+    type TokenId = u32;
+    type ClassData = ();
+    type TokenData = Kitty;
+    type MaxClassMetadata = MaxClassMetadata;
+    type MaxTokenMetadata = MaxTokenMetadata;
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    let mut t: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+    let mut storage = frame_system::GenesisConfig::default()
         .build_storage::<Test>()
-        .unwrap()
-        .into();
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(100, 1000), (101, 1000), (102, 1000)],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    // creates the kitties NFT class
+    GenesisBuild::<Test>::assimilate_storage(&kitties::GenesisConfig, &mut storage).unwrap();
+
+    let mut t: sp_io::TestExternalities = storage.into();
     // events on block 0 are ignored, so set it to 1
     t.execute_with(|| System::set_block_number(1));
     t
@@ -113,17 +214,19 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 #[test]
 fn can_create() {
     new_test_ext().execute_with(|| {
-        assert_ok!(KittiesModule::create_kitty(Origin::signed(100)));
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
 
         let kitty = Kitty([
             59, 250, 138, 82, 209, 39, 141, 109, 163, 238, 183, 145, 235, 168, 18, 122,
         ]);
 
-        assert_eq!(KittiesModule::kitties(100, 0), Some(kitty.clone()));
-        assert_eq!(KittiesModule::next_kitty_id(), 1);
+        assert_eq!(KittiesModule::kitties(&100, (0, 0)), Some(kitty.clone()));
+        assert_eq!(Nft::next_token_id(KittiesModule::class_id()), 1);
 
         System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittyCreated(
-            100, 0, kitty,
+            100,
+            (0, 0),
+            kitty,
         )));
     });
 }
@@ -140,40 +243,3053 @@ fn gender() {
 #[test]
 fn can_breed() {
     new_test_ext().execute_with(|| {
-        assert_ok!(KittiesModule::create_kitty(Origin::signed(100)));
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
 
         // inject to have a different genders of minted kitties
         // System::set_extrinsic_index(1);
         MockRandom::set(H256::from([2; 32]));
 
-        assert_ok!(KittiesModule::create_kitty(Origin::signed(100)));
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
 
         // test an error condition
         // asser_nop ensures no state change happened on an tx that errored
         assert_noop!(
-            KittiesModule::breed_kitties(Origin::signed(100), 0, 11),
+            KittiesModule::breed_kitties(Origin::signed(100), (0, 0), (0, 11)),
             Error::<Test>::InvalidKittyId
         );
         assert_noop!(
-            KittiesModule::breed_kitties(Origin::signed(100), 0, 0),
+            KittiesModule::breed_kitties(Origin::signed(100), (0, 0), (0, 0)),
             Error::<Test>::SameGender
         );
         assert_noop!(
-            KittiesModule::breed_kitties(Origin::signed(101), 0, 1),
+            KittiesModule::breed_kitties(Origin::signed(101), (0, 0), (0, 1)),
             Error::<Test>::InvalidKittyId
         );
 
-        assert_ok!(KittiesModule::breed_kitties(Origin::signed(100), 0, 1));
+        assert_ok!(KittiesModule::breed_kitties(Origin::signed(100), (0, 0), (0, 1)));
 
-        let kitty = Kitty([
-            187, 250, 235, 118, 211, 247, 237, 253, 187, 239, 191, 185, 239, 171, 211, 122,
-        ]);
+        let mut dna = KittiesModule::kitties(&100, (0, 0)).unwrap().0;
+        dna[8..].copy_from_slice(&KittiesModule::kitties(&100, (0, 1)).unwrap().0[8..]);
+        let kitty = Kitty(dna);
 
-        assert_eq!(KittiesModule::kitties(100, 2), Some(kitty.clone()));
-        assert_eq!(KittiesModule::next_kitty_id(), 3);
+        assert_eq!(KittiesModule::kitties(&100, (0, 2)), Some(kitty.clone()));
+        assert_eq!(Nft::next_token_id(KittiesModule::class_id()), 3);
+
+        let info = KittyInfo {
+            generation: 1,
+            parents: Some(((0, 0), (0, 1))),
+            birth_block: 1,
+        };
+        assert_eq!(KittiesModule::kitty_info((0, 2)), Some(info.clone()));
 
         System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittyBred(
-            100u64, 2u32, kitty,
+            100u64,
+            (0, 2),
+            kitty,
+            info,
+        )));
+    });
+}
+
+// mint a kitty with the given DNA, bypassing the randomness of `create_kitty`
+fn mint(owner: u64, dna: [u8; 16]) -> (u32, u32) {
+    let class_id = KittiesModule::class_id();
+    let kitty_id = (class_id, Nft::mint(&owner, class_id, Vec::new(), Kitty(dna)).unwrap());
+    KittyInfos::<Test>::insert(
+        kitty_id,
+        KittyInfo {
+            generation: 0,
+            parents: None,
+            birth_block: System::block_number(),
+        },
+    );
+    KittiesOwned::<Test>::mutate(owner, |count| *count += 1);
+    kitty_id
+}
+
+const MALE: [u8; 16] = [0; 16];
+const FEMALE: [u8; 16] = [1; 16];
+
+#[test]
+fn created_kitty_is_generation_zero() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(7);
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+
+        assert_eq!(
+            KittiesModule::kitty_info((0, 0)),
+            Some(KittyInfo {
+                generation: 0,
+                parents: None,
+                birth_block: 7,
+            })
+        );
+    });
+}
+
+#[test]
+fn generation_is_one_more_than_the_oldest_parent() {
+    new_test_ext().execute_with(|| {
+        let father = mint(100, MALE);
+        let mother = mint(100, FEMALE);
+
+        // the mock breeder takes the gender from the first parent
+        assert_ok!(KittiesModule::breed_kitties(Origin::signed(100), mother, father));
+        let child = (0, 2);
+        assert_eq!(KittiesModule::kitty_info(child).unwrap().generation, 1);
+
+        System::set_block_number(50);
+        let other = mint(100, MALE);
+        assert_ok!(KittiesModule::breed_kitties(Origin::signed(100), other, child));
+        assert_eq!(
+            KittiesModule::kitty_info((0, 4)),
+            Some(KittyInfo {
+                generation: 2,
+                parents: Some((other, child)),
+                birth_block: 50,
+            })
+        );
+    });
+}
+
+#[test]
+fn breeding_cooldown_grows_with_generation_and_breed_count() {
+    assert_eq!(KittiesModule::breeding_cooldown(0, 1), 10);
+    assert_eq!(KittiesModule::breeding_cooldown(0, 2), 20);
+    assert_eq!(KittiesModule::breeding_cooldown(2, 1), 30);
+    // capped
+    assert_eq!(KittiesModule::breeding_cooldown(3, 1), 35);
+    assert_eq!(KittiesModule::breeding_cooldown(u32::MAX, u32::MAX), 35);
+}
+
+#[test]
+fn breeding_puts_parents_on_cooldown() {
+    new_test_ext().execute_with(|| {
+        let father = mint(100, MALE);
+        let mother = mint(100, FEMALE);
+        assert_eq!(KittiesModule::ready_to_breed_at(father), 0);
+
+        assert_ok!(KittiesModule::breed_kitties(Origin::signed(100), father, mother));
+        assert_eq!(
+            KittiesModule::breeding_state(father),
+            BreedingState {
+                breed_count: 1,
+                ready_at: 11,
+            }
+        );
+        assert_eq!(KittiesModule::ready_to_breed_at(mother), 11);
+
+        System::set_block_number(10);
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), father, mother),
+            Error::<Test>::KittyOnCooldown
+        );
+
+        // a fresh partner doesn't help while one of the parents rests
+        let other_mother = mint(100, FEMALE);
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), father, other_mother),
+            Error::<Test>::KittyOnCooldown
+        );
+
+        System::set_block_number(11);
+        assert_ok!(KittiesModule::breed_kitties(Origin::signed(100), father, mother));
+        // second breeding of a generation 0 kitty
+        assert_eq!(KittiesModule::ready_to_breed_at(father), 31);
+    });
+}
+
+// breed two kitties after their cooldowns are over, returns the child id
+fn breed(owner: u64, kitty_id_1: (u32, u32), kitty_id_2: (u32, u32)) -> (u32, u32) {
+    System::set_block_number(System::block_number() + MaxBreedingCooldown::get());
+    assert_ok!(KittiesModule::breed_kitties(Origin::signed(owner), kitty_id_1, kitty_id_2));
+    (kitty_id_1.0, Nft::next_token_id(kitty_id_1.0) - 1)
+}
+
+#[test]
+fn cannot_breed_parent_and_child() {
+    new_test_ext().execute_with(|| {
+        let father = mint(100, MALE);
+        let mother = mint(100, FEMALE);
+        let daughter = breed(100, mother, father);
+        let son = breed(100, father, mother);
+
+        System::set_block_number(System::block_number() + MaxBreedingCooldown::get());
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), father, daughter),
+            Error::<Test>::Inbreeding
+        );
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), son, mother),
+            Error::<Test>::Inbreeding
+        );
+    });
+}
+
+#[test]
+fn cannot_breed_siblings() {
+    new_test_ext().execute_with(|| {
+        let father = mint(100, MALE);
+        let mother = mint(100, FEMALE);
+        let daughter = breed(100, mother, father);
+        let son = breed(100, father, mother);
+
+        System::set_block_number(System::block_number() + MaxBreedingCooldown::get());
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), son, daughter),
+            Error::<Test>::Inbreeding
+        );
+    });
+}
+
+#[test]
+fn cannot_breed_half_siblings() {
+    new_test_ext().execute_with(|| {
+        let father = mint(100, MALE);
+        let mother = mint(100, FEMALE);
+        let other_mother = mint(100, FEMALE);
+        let son = breed(100, father, mother);
+        let half_sister = breed(100, other_mother, father);
+
+        System::set_block_number(System::block_number() + MaxBreedingCooldown::get());
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), son, half_sister),
+            Error::<Test>::Inbreeding
+        );
+    });
+}
+
+#[test]
+fn inbreeding_depth_reaches_grandparents_and_cousins() {
+    new_test_ext().execute_with(|| {
+        let grandfather = mint(100, MALE);
+        let grandmother = mint(100, FEMALE);
+        let daughter = breed(100, grandmother, grandfather);
+        let son = breed(100, grandfather, grandmother);
+
+        let other_male = mint(100, MALE);
+        let other_female = mint(100, FEMALE);
+        let granddaughter = breed(100, daughter, other_male);
+        let grandson = breed(100, son, other_female);
+
+        System::set_block_number(System::block_number() + MaxBreedingCooldown::get());
+
+        // one generation back they share no ancestor
+        assert!(!KittiesModule::are_related(grandfather, granddaughter));
+        assert!(!KittiesModule::are_related(grandson, granddaughter));
+
+        InbreedingDepth::set(2);
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), grandfather, granddaughter),
+            Error::<Test>::Inbreeding
+        );
+        // cousins
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), grandson, granddaughter),
+            Error::<Test>::Inbreeding
+        );
+
+        // unrelated kitties can still breed
+        let stranger = mint(100, MALE);
+        assert_ok!(KittiesModule::breed_kitties(Origin::signed(100), stranger, granddaughter));
+    });
+}
+
+#[test]
+fn inbreeding_check_can_be_disabled() {
+    new_test_ext().execute_with(|| {
+        InbreedingDepth::set(0);
+
+        let father = mint(100, MALE);
+        let mother = mint(100, FEMALE);
+        let daughter = breed(100, mother, father);
+        breed(100, father, daughter);
+    });
+}
+
+#[test]
+fn migrate_to_v2_records_existing_kitties() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::<Test>::put(Releases::V1);
+
+        // kitties minted before kitty info existed
+        let kitty_id = Nft::mint(&100, 0, Vec::new(), Kitty(MALE)).unwrap();
+        let known = Nft::mint(&100, 0, Vec::new(), Kitty(FEMALE)).unwrap();
+        put_v3_kitty_info(
+            known,
+            KittyInfo {
+                generation: 3,
+                parents: None,
+                birth_block: 1,
+            },
+        );
+
+        migrations::migrate_to_v2::<Test>();
+        assert_eq!(StorageVersion::<Test>::get(), Releases::V2);
+        migrations::migrate_to_v3::<Test>();
+        migrations::migrate_to_v4::<Test>();
+
+        assert_eq!(
+            KittiesModule::kitty_info((0, kitty_id)),
+            Some(KittyInfo {
+                generation: 0,
+                parents: None,
+                birth_block: 0,
+            })
+        );
+        // already recorded kitties are untouched
+        assert_eq!(KittiesModule::kitty_info((0, known)).unwrap().generation, 3);
+    });
+}
+
+// store kitty info keyed by token id, like before collections existed
+fn put_v3_kitty_info(kitty_id: u32, info: KittyInfo<u32, u64>) {
+    frame_support::storage::migration::put_storage_value(
+        b"KittiesModule",
+        b"KittyInfos",
+        &Blake2_128Concat::hash(&kitty_id.encode()),
+        info,
+    );
+}
+
+#[test]
+fn phenotype_decodes_each_trait_from_its_gene() {
+    use phenotype::*;
+
+    // the second allele of every trait gene is the most recessive one, so the first one shows,
+    // accessory slots are only filled by a zero allele and the other one picks the accessory
+    let dna = [1, 0x2f, 0x3f, 0x4f, 0x5f, 0x6f, 0x7f, 0x8f, 0x00, 0x01, 0x20, 0x1f, 0, 0, 0, 0];
+    assert_eq!(
+        Kitty(dna).phenotype(),
+        Phenotype {
+            gender: KittyGender::Female,
+            body_colour: Colour::Ginger,
+            eye_colour: EyeColour::Copper,
+            pattern: Pattern::Calico,
+            pattern_colour: Colour::Chocolate,
+            ear_shape: EarShape::Rounded,
+            tail: Tail::Bobbed,
+            fur: FurLength::Hairless,
+            accessories: [
+                Some(Accessory::Hat),
+                Some(Accessory::Bowtie),
+                Some(Accessory::Glasses),
+                None
+            ],
+        }
+    );
+}
+
+#[test]
+fn accessories_are_rare() {
+    use genetics::Gene;
+    use phenotype::Accessory;
+
+    let worn = (0..=255u8).filter(|gene| Accessory::from_gene(Gene(*gene)).is_some()).count();
+    assert_eq!(worn, 31);
+
+    assert_eq!(Accessory::from_gene(Gene::new(0, 0)), Some(Accessory::Hat));
+    assert_eq!(Accessory::from_gene(Gene::new(5, 0)), Some(Accessory::Crown));
+    assert_eq!(Accessory::from_gene(Gene::new(1, 1)), None);
+}
+
+#[test]
+fn every_dna_value_decodes_to_a_valid_phenotype() {
+    use genetics::Gene;
+    use phenotype::*;
+
+    // every trait is read from a single gene, so walking all values of every gene covers every DNA
+    for gene in 0..16 {
+        for value in 0..=255u8 {
+            let mut dna = [0u8; 16];
+            dna[gene] = value;
+
+            let phenotype = Kitty(dna).phenotype();
+            let baseline = Kitty([0u8; 16]).phenotype();
+
+            assert_eq!(phenotype.gender == baseline.gender, gene != GENDER_GENE || value % 2 == 0);
+            assert!((phenotype.body_colour as u8) < Colour::VARIANTS);
+            assert!((phenotype.eye_colour as u8) < EyeColour::VARIANTS);
+            assert!((phenotype.pattern as u8) < Pattern::VARIANTS);
+            assert!((phenotype.pattern_colour as u8) < Colour::VARIANTS);
+            assert!((phenotype.ear_shape as u8) < EarShape::VARIANTS);
+            assert!((phenotype.tail as u8) < Tail::VARIANTS);
+            assert!((phenotype.fur as u8) < FurLength::VARIANTS);
+            for accessory in phenotype.accessories.iter().flatten() {
+                assert!((*accessory as u8) < Accessory::VARIANTS);
+            }
+
+            // a gene only affects its own trait
+            if gene >= 12 {
+                assert_eq!(phenotype, baseline);
+            }
+        }
+    }
+
+    // every variant is reachable
+    let decoded = |f: fn(u8) -> u8| {
+        let mut seen = (0..=255u8).map(f).collect::<Vec<_>>();
+        seen.sort();
+        seen.dedup();
+        seen.len() as u8
+    };
+    assert_eq!(decoded(|g| Colour::from_gene(g) as u8), Colour::VARIANTS);
+    assert_eq!(decoded(|g| EyeColour::from_gene(g) as u8), EyeColour::VARIANTS);
+    assert_eq!(decoded(|g| Pattern::from_gene(g) as u8), Pattern::VARIANTS);
+    assert_eq!(decoded(|g| EarShape::from_gene(g) as u8), EarShape::VARIANTS);
+    assert_eq!(decoded(|g| Tail::from_gene(g) as u8), Tail::VARIANTS);
+    assert_eq!(decoded(|g| FurLength::from_gene(g) as u8), FurLength::VARIANTS);
+    assert_eq!(
+        decoded(|g| Accessory::from_gene(Gene(g)).map_or(Accessory::VARIANTS, |a| a as u8)),
+        Accessory::VARIANTS + 1
+    );
+}
+
+#[test]
+fn dominant_allele_is_expressed() {
+    use genetics::Gene;
+
+    assert_eq!(Gene::new(2, 9).expressed(), 2);
+    assert_eq!(Gene::new(9, 2).expressed(), 2);
+    assert_eq!(Gene::new(9, 2).recessive(), 9);
+    assert_eq!(Gene::new(7, 7).expressed(), 7);
+    assert_eq!(Gene::new(9, 2).alleles(), [9, 2]);
+}
+
+#[test]
+fn mendelian_child_inherits_one_allele_from_each_parent() {
+    use genetics::{mendelian, Gene};
+
+    let dna1 = [0x12; 16];
+    let dna2 = [0x34; 16];
+
+    for seed in 0..=255u8 {
+        let child = mendelian(&dna1, &dna2, &[seed; 16], Perbill::zero());
+        for gene in child.iter() {
+            let [from_parent1, from_parent2] = Gene(*gene).alleles();
+            assert!(from_parent1 == 1 || from_parent1 == 2);
+            assert!(from_parent2 == 3 || from_parent2 == 4);
+        }
+    }
+}
+
+#[test]
+fn mendelian_recessive_trait_can_skip_a_generation() {
+    use genetics::{mendelian, Gene};
+
+    // both parents show allele 1, but carry the recessive allele 8
+    let dna = [Gene::new(1, 8).0; 16];
+
+    let mut children = (0..=255u8).map(|seed| mendelian(&dna, &dna, &[seed; 16], Perbill::zero()));
+    assert!(children.any(|child| child.iter().any(|gene| Gene(*gene).expressed() == 8)));
+}
+
+#[test]
+fn mendelian_mutation_probability() {
+    use genetics::mendelian;
+
+    let dna = [0x11; 16];
+
+    // never mutates
+    for seed in 0..=255u8 {
+        assert_eq!(mendelian(&dna, &dna, &[seed; 16], Perbill::zero()), dna);
+    }
+
+    // always mutates, a random allele can match the parents one so check across many seeds
+    assert!((0..=255u8).any(|seed| mendelian(&dna, &dna, &[seed; 16], Perbill::one()) != dna));
+}
+
+#[test]
+fn breeders() {
+    use genetics::*;
+
+    parameter_types! {
+        pub const NoMutation: Perbill = Perbill::zero();
+    }
+
+    let parent1 = Kitty([0b10101010; 16]);
+    let parent2 = Kitty([0b00001111; 16]);
+    let random = [0b00000001; 16];
+
+    assert_eq!(
+        LegacyBreeder::breed(&parent1, &parent2, &random),
+        Ok(Kitty([0b10101011; 16]))
+    );
+    // legacy is the default
+    assert_eq!(
+        <() as Breeder>::breed(&parent1, &parent2, &random),
+        Ok(Kitty([0b10101011; 16]))
+    );
+    assert_eq!(
+        MendelianBreeder::<NoMutation>::breed(&parent1, &parent2, &random),
+        Ok(Kitty(mendelian(&parent1.0, &parent2.0, &random, Perbill::zero())))
+    );
+}
+
+#[test]
+fn breeder_error_fails_breeding() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        MockRandom::set(H256::from([2; 32]));
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+
+        MockBreederFails::set(true);
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), (0, 0), (0, 1)),
+            DispatchError::Other("breeding failed")
+        );
+    });
+}
+
+#[test]
+fn can_set_sire_price() {
+    new_test_ext().execute_with(|| {
+        let sire = mint(100, MALE);
+        let queen = mint(100, FEMALE);
+
+        assert_noop!(
+            KittiesModule::set_sire_price(Origin::signed(101), sire, Some(50)),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::set_sire_price(Origin::signed(100), queen, Some(50)),
+            Error::<Test>::NotMale
+        );
+
+        assert_ok!(KittiesModule::set_sire_price(Origin::signed(100), sire, Some(50)));
+        assert_eq!(KittiesModule::sire_prices(sire), Some(50));
+        System::assert_last_event(Event::KittiesModule(
+            crate::Event::<Test>::SirePriceUpdated(100, sire, Some(50)),
+        ));
+
+        assert_ok!(KittiesModule::set_sire_price(Origin::signed(100), sire, None));
+        assert_eq!(KittiesModule::sire_prices(sire), None);
+    });
+}
+
+#[test]
+fn can_breed_with_sire() {
+    new_test_ext().execute_with(|| {
+        let sire = mint(100, MALE);
+        let queen = mint(101, FEMALE);
+
+        assert_noop!(
+            KittiesModule::breed_with_sire(Origin::signed(101), queen, sire, 50),
+            Error::<Test>::NotForSiring
+        );
+
+        assert_ok!(KittiesModule::set_sire_price(Origin::signed(100), sire, Some(50)));
+
+        assert_noop!(
+            KittiesModule::breed_with_sire(Origin::signed(101), queen, sire, 49),
+            Error::<Test>::PriceTooLow
+        );
+        // only the own kitty can be the mother
+        assert_noop!(
+            KittiesModule::breed_with_sire(Origin::signed(102), queen, sire, 50),
+            Error::<Test>::InvalidKittyId
+        );
+
+        assert_ok!(KittiesModule::breed_with_sire(Origin::signed(101), queen, sire, 50));
+
+        let child = (0, 2);
+        assert!(KittiesModule::kitties(&101, child).is_some());
+        assert_eq!(KittiesModule::kitty_info(child).unwrap().parents, Some((queen, sire)));
+        assert_eq!(Balances::free_balance(101), 950);
+        assert_eq!(Balances::free_balance(100), 1050);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::SireFeePaid(
+            101, 100, sire, 50,
+        )));
+
+        // the sire stays on offer, but needs to rest
+        assert_eq!(KittiesModule::sire_prices(sire), Some(50));
+        assert_noop!(
+            KittiesModule::breed_with_sire(Origin::signed(101), child, sire, 50),
+            Error::<Test>::KittyOnCooldown
+        );
+    });
+}
+
+#[test]
+fn failed_breeding_does_not_charge_the_fee() {
+    new_test_ext().execute_with(|| {
+        let sire = mint(100, MALE);
+        let queen = mint(101, FEMALE);
+        assert_ok!(KittiesModule::set_sire_price(Origin::signed(100), sire, Some(50)));
+
+        MockBreederFails::set(true);
+        assert_noop!(
+            KittiesModule::breed_with_sire(Origin::signed(101), queen, sire, 50),
+            DispatchError::Other("breeding failed")
+        );
+        assert_eq!(Balances::free_balance(101), 1000);
+    });
+}
+
+#[test]
+fn transfer_withdraws_the_sire_offer() {
+    new_test_ext().execute_with(|| {
+        let sire = mint(100, MALE);
+        assert_ok!(KittiesModule::set_sire_price(Origin::signed(100), sire, Some(50)));
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, sire));
+        assert_eq!(KittiesModule::sire_prices(sire), None);
+    });
+}
+
+fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        KittiesModule::on_initialize(System::block_number());
+    }
+}
+
+#[test]
+fn can_create_auction() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), kitty_id, Some(10)));
+
+        assert_noop!(
+            KittiesModule::create_auction(Origin::signed(101), kitty_id, 10, 20),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::create_auction(Origin::signed(100), kitty_id, 10, 1),
+            Error::<Test>::InvalidAuctionEnd
+        );
+        assert_noop!(
+            KittiesModule::create_auction(Origin::signed(100), kitty_id, 10, 102),
+            Error::<Test>::InvalidAuctionEnd
+        );
+
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id, 10, 20));
+        assert_eq!(
+            KittiesModule::auctions(kitty_id),
+            Some(Auction {
+                seller: 100,
+                reserve_price: 10,
+                end: 20,
+                best_bid: None,
+            })
+        );
+        // delisted from the fixed price sale
+        assert_eq!(KittiesModule::kitty_prices(kitty_id), None);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::AuctionCreated(
+            100, kitty_id, 10, 20,
         )));
+
+        // the kitty is locked while auctioned
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(100), 101, kitty_id),
+            Error::<Test>::KittyInAuction
+        );
+        assert_noop!(
+            KittiesModule::set_price(Origin::signed(100), kitty_id, Some(10)),
+            Error::<Test>::KittyInAuction
+        );
+        assert_noop!(
+            KittiesModule::create_auction(Origin::signed(100), kitty_id, 10, 20),
+            Error::<Test>::KittyInAuction
+        );
+    });
+}
+
+#[test]
+fn bids_reserve_funds() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id, 100, 20));
+
+        assert_noop!(
+            KittiesModule::bid(Origin::signed(101), (0, kitty_id.1 + 1), 100),
+            Error::<Test>::AuctionNotFound
+        );
+        assert_noop!(
+            KittiesModule::bid(Origin::signed(100), kitty_id, 100),
+            Error::<Test>::BuyFromSelf
+        );
+        assert_noop!(
+            KittiesModule::bid(Origin::signed(101), kitty_id, 99),
+            Error::<Test>::BidTooLow
+        );
+
+        assert_ok!(KittiesModule::bid(Origin::signed(101), kitty_id, 100));
+        assert_eq!(Balances::reserved_balance(101), 100);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::BidPlaced(
+            101, kitty_id, 100,
+        )));
+
+        assert_noop!(
+            KittiesModule::bid(Origin::signed(102), kitty_id, 100),
+            Error::<Test>::BidTooLow
+        );
+
+        // outbid
+        assert_ok!(KittiesModule::bid(Origin::signed(102), kitty_id, 150));
+        assert_eq!(Balances::reserved_balance(101), 0);
+        assert_eq!(Balances::reserved_balance(102), 150);
+
+        // raising the own bid reserves the difference
+        assert_ok!(KittiesModule::bid(Origin::signed(102), kitty_id, 200));
+        assert_eq!(Balances::reserved_balance(102), 200);
+        assert_eq!(Balances::free_balance(102), 800);
+    });
+}
+
+#[test]
+fn late_bid_extends_the_auction() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id, 100, 20));
+
+        // early bids don't change the end
+        run_to_block(15);
+        assert_ok!(KittiesModule::bid(Origin::signed(101), kitty_id, 100));
+        assert_eq!(KittiesModule::auctions(kitty_id).unwrap().end, 20);
+
+        run_to_block(18);
+        assert_ok!(KittiesModule::bid(Origin::signed(102), kitty_id, 150));
+        assert_eq!(KittiesModule::auctions(kitty_id).unwrap().end, 23);
+        System::assert_has_event(Event::KittiesModule(crate::Event::<Test>::AuctionExtended(
+            kitty_id, 23,
+        )));
+
+        // not settled at the original end
+        run_to_block(20);
+        assert!(KittiesModule::auctions(kitty_id).is_some());
+
+        run_to_block(23);
+        assert!(KittiesModule::auctions(kitty_id).is_none());
+        assert!(KittiesModule::kitties(&102, kitty_id).is_some());
+    });
+}
+
+#[test]
+fn auction_settles_at_the_end() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::set_sire_price(Origin::signed(100), kitty_id, Some(10)));
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id, 100, 20));
+        assert_ok!(KittiesModule::bid(Origin::signed(101), kitty_id, 100));
+        assert_ok!(KittiesModule::bid(Origin::signed(102), kitty_id, 150));
+
+        run_to_block(20);
+
+        assert_eq!(KittiesModule::auctions(kitty_id), None);
+        assert!(KittiesModule::kitties(&102, kitty_id).is_some());
+        assert_eq!(KittiesModule::sire_prices(kitty_id), None);
+        assert_eq!(Balances::free_balance(100), 1150);
+        assert_eq!(Balances::free_balance(101), 1000);
+        assert_eq!(Balances::free_balance(102), 850);
+        assert_eq!(Balances::reserved_balance(102), 0);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::AuctionSettled(
+            100, 102, kitty_id, 150,
+        )));
+
+        // the new owner can trade the kitty
+        assert_ok!(KittiesModule::transfer(Origin::signed(102), 101, kitty_id));
+    });
+}
+
+#[test]
+fn auction_without_bids_closes() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id, 100, 20));
+
+        run_to_block(20);
+
+        assert_eq!(KittiesModule::auctions(kitty_id), None);
+        assert!(KittiesModule::kitties(&100, kitty_id).is_some());
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::AuctionClosed(
+            100, kitty_id,
+        )));
+    });
+}
+
+#[test]
+fn items_ending_in_a_full_block_move_to_the_next_one() {
+    new_test_ext().execute_with(|| {
+        MaxScheduledPerBlock::set(2);
+        let kitty_id_1 = mint(100, MALE);
+        let kitty_id_2 = mint(100, MALE);
+        let kitty_id_3 = mint(100, MALE);
+        let kitty_id_4 = mint(100, MALE);
+
+        // auctions, gift offers and rentals share the slots of a block
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id_1, 100, 11));
+        assert_ok!(KittiesModule::offer_transfer(Origin::signed(100), 101, kitty_id_2));
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id_3, 100, 11));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::AuctionCreated(
+            100, kitty_id_3, 100, 12,
+        )));
+        assert_eq!(KittiesModule::auctions(kitty_id_3).unwrap().end, 12);
+
+        // a late bid extending the auction into a full block extends it past that block
+        run_to_block(7);
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id_4, 100, 12));
+        assert_ok!(KittiesModule::bid(Origin::signed(101), kitty_id_1, 100));
+        System::assert_has_event(Event::KittiesModule(crate::Event::<Test>::AuctionExtended(
+            kitty_id_1, 13,
+        )));
+        assert_eq!(KittiesModule::auctions(kitty_id_1).unwrap().end, 13);
+        assert_eq!(ScheduledCount::<Test>::get(11), 1);
+        assert_eq!(ScheduledCount::<Test>::get(12), 2);
+
+        // removing an item early frees its slot
+        assert_ok!(KittiesModule::cancel_auction(Origin::signed(100), kitty_id_4));
+        assert_eq!(ScheduledCount::<Test>::get(12), 1);
+
+        run_to_block(12);
+        assert!(KittiesModule::auctions(kitty_id_1).is_some());
+        run_to_block(13);
+        assert!(KittiesModule::kitties(&101, kitty_id_1).is_some());
+        assert_eq!(ScheduledCount::<Test>::iter().count(), 0);
+    });
+}
+
+#[test]
+fn can_cancel_auction_without_bids() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id, 100, 20));
+
+        assert_noop!(
+            KittiesModule::cancel_auction(Origin::signed(101), kitty_id),
+            Error::<Test>::NotOwner
+        );
+
+        assert_ok!(KittiesModule::cancel_auction(Origin::signed(100), kitty_id));
+        assert_eq!(KittiesModule::auctions(kitty_id), None);
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, kitty_id));
+
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id, 100, 20));
+        assert_ok!(KittiesModule::bid(Origin::signed(101), kitty_id, 100));
+        assert_noop!(
+            KittiesModule::cancel_auction(Origin::signed(100), kitty_id),
+            Error::<Test>::AuctionHasBids
+        );
+    });
+}
+
+#[test]
+fn dutch_auction_price_decays_linearly() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), kitty_id, Some(10)));
+
+        assert_noop!(
+            KittiesModule::create_dutch_auction(Origin::signed(101), kitty_id, 1000, 100, 10),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::create_dutch_auction(Origin::signed(100), kitty_id, 100, 1000, 10),
+            Error::<Test>::InvalidDutchAuction
+        );
+        assert_noop!(
+            KittiesModule::create_dutch_auction(Origin::signed(100), kitty_id, 1000, 100, 0),
+            Error::<Test>::InvalidDutchAuction
+        );
+
+        assert_ok!(KittiesModule::create_dutch_auction(
+            Origin::signed(100),
+            kitty_id,
+            1000,
+            100,
+            10
+        ));
+        assert_eq!(KittiesModule::kitty_prices(kitty_id), None);
+        System::assert_last_event(Event::KittiesModule(
+            crate::Event::<Test>::DutchAuctionCreated(100, kitty_id, 1000, 100, 10),
+        ));
+
+        assert_eq!(KittiesModule::current_dutch_price(kitty_id), Some(1000));
+        run_to_block(2);
+        assert_eq!(KittiesModule::current_dutch_price(kitty_id), Some(910));
+        run_to_block(6);
+        assert_eq!(KittiesModule::current_dutch_price(kitty_id), Some(550));
+        // stays at the floor price after the duration
+        run_to_block(11);
+        assert_eq!(KittiesModule::current_dutch_price(kitty_id), Some(100));
+        run_to_block(50);
+        assert_eq!(KittiesModule::current_dutch_price(kitty_id), Some(100));
+
+        // the kitty is locked while auctioned
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(100), 101, kitty_id),
+            Error::<Test>::KittyInAuction
+        );
+        assert_noop!(
+            KittiesModule::create_auction(Origin::signed(100), kitty_id, 10, 60),
+            Error::<Test>::KittyInAuction
+        );
+    });
+}
+
+#[test]
+fn can_buy_from_dutch_auction() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::set_sire_price(Origin::signed(100), kitty_id, Some(10)));
+        assert_ok!(KittiesModule::create_dutch_auction(
+            Origin::signed(100),
+            kitty_id,
+            1000,
+            100,
+            10
+        ));
+
+        assert_noop!(
+            KittiesModule::buy_dutch(Origin::signed(101), (0, kitty_id.1 + 1), 1000),
+            Error::<Test>::AuctionNotFound
+        );
+        assert_noop!(
+            KittiesModule::buy_dutch(Origin::signed(100), kitty_id, 1000),
+            Error::<Test>::BuyFromSelf
+        );
+
+        run_to_block(6);
+        assert_noop!(
+            KittiesModule::buy_dutch(Origin::signed(101), kitty_id, 549),
+            Error::<Test>::PriceTooLow
+        );
+
+        assert_ok!(KittiesModule::buy_dutch(Origin::signed(101), kitty_id, 600));
+
+        assert_eq!(KittiesModule::dutch_auctions(kitty_id), None);
+        assert!(KittiesModule::kitties(&101, kitty_id).is_some());
+        assert_eq!(KittiesModule::sire_prices(kitty_id), None);
+        // paid the live price, not the max price
+        assert_eq!(Balances::free_balance(100), 1550);
+        assert_eq!(Balances::free_balance(101), 450);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittySold(
+            100, 101, kitty_id, 550,
+        )));
+    });
+}
+
+#[test]
+fn can_cancel_dutch_auction() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::create_dutch_auction(
+            Origin::signed(100),
+            kitty_id,
+            1000,
+            100,
+            10
+        ));
+
+        assert_noop!(
+            KittiesModule::cancel_dutch_auction(Origin::signed(101), kitty_id),
+            Error::<Test>::NotOwner
+        );
+
+        assert_ok!(KittiesModule::cancel_dutch_auction(Origin::signed(100), kitty_id));
+        assert_eq!(KittiesModule::dutch_auctions(kitty_id), None);
+        assert_eq!(KittiesModule::current_dutch_price(kitty_id), None);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::AuctionClosed(
+            100, kitty_id,
+        )));
+        assert_noop!(
+            KittiesModule::buy_dutch(Origin::signed(101), kitty_id, 1000),
+            Error::<Test>::AuctionNotFound
+        );
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, kitty_id));
+    });
+}
+
+#[test]
+fn can_make_and_withdraw_offer() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+
+        assert_noop!(
+            KittiesModule::make_offer(Origin::signed(101), (0, kitty_id.1 + 1), 100, 10),
+            Error::<Test>::InvalidKittyId
+        );
+        assert_noop!(
+            KittiesModule::make_offer(Origin::signed(100), kitty_id, 100, 10),
+            Error::<Test>::BuyFromSelf
+        );
+        assert_noop!(
+            KittiesModule::make_offer(Origin::signed(101), kitty_id, 100, 1),
+            Error::<Test>::OfferExpired
+        );
+        assert_noop!(
+            KittiesModule::make_offer(Origin::signed(101), kitty_id, 100, 52),
+            Error::<Test>::OfferExpiryTooFar
+        );
+        assert_noop!(
+            KittiesModule::make_offer(Origin::signed(101), kitty_id, 0, 10),
+            Error::<Test>::OfferTooLow
+        );
+
+        assert_ok!(KittiesModule::make_offer(Origin::signed(101), kitty_id, 100, 10));
+        assert_eq!(
+            KittiesModule::offers(kitty_id, 101),
+            Some(Offer {
+                amount: 100,
+                expiry: 10,
+            })
+        );
+        assert_eq!(Balances::reserved_balance(101), 100);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::OfferMade(
+            101, kitty_id, 100, 10,
+        )));
+
+        // a new offer replaces the previous one
+        assert_ok!(KittiesModule::make_offer(Origin::signed(101), kitty_id, 150, 20));
+        assert_eq!(Balances::reserved_balance(101), 150);
+        assert_eq!(Balances::free_balance(101), 850);
+
+        assert_noop!(
+            KittiesModule::withdraw_offer(Origin::signed(102), kitty_id),
+            Error::<Test>::OfferNotFound
+        );
+
+        assert_ok!(KittiesModule::withdraw_offer(Origin::signed(101), kitty_id));
+        assert_eq!(KittiesModule::offers(kitty_id, 101), None);
+        assert_eq!(Balances::reserved_balance(101), 0);
+        assert_eq!(Balances::free_balance(101), 1000);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::OfferWithdrawn(
+            101, kitty_id,
+        )));
+    });
+}
+
+#[test]
+fn offers_per_kitty_are_limited() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+
+        for buyer in 101..=104 {
+            Balances::make_free_balance_be(&buyer, 1000);
+        }
+        for buyer in 101..=103 {
+            assert_ok!(KittiesModule::make_offer(Origin::signed(buyer), kitty_id, 10, 10));
+        }
+
+        assert_noop!(
+            KittiesModule::make_offer(Origin::signed(104), kitty_id, 10, 10),
+            Error::<Test>::TooManyOffers
+        );
+        // replacing an offer is still possible
+        assert_ok!(KittiesModule::make_offer(Origin::signed(101), kitty_id, 20, 10));
+
+        // expired offers are refunded to make room
+        run_to_block(10);
+        assert_ok!(KittiesModule::make_offer(Origin::signed(104), kitty_id, 10, 20));
+        assert_eq!(Offers::<Test>::iter_prefix(kitty_id).count(), 1);
+        for buyer in 101..=103 {
+            assert_eq!(KittiesModule::offers(kitty_id, buyer), None);
+            assert_eq!(Balances::reserved_balance(buyer), 0);
+        }
+    });
+}
+
+#[test]
+fn can_accept_offer() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), kitty_id, Some(500)));
+        assert_ok!(KittiesModule::set_sire_price(Origin::signed(100), kitty_id, Some(10)));
+        assert_ok!(KittiesModule::make_offer(Origin::signed(101), kitty_id, 200, 10));
+        assert_ok!(KittiesModule::make_offer(Origin::signed(102), kitty_id, 100, 10));
+
+        assert_noop!(
+            KittiesModule::accept_offer(Origin::signed(101), kitty_id, 101, 200),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::accept_offer(Origin::signed(100), kitty_id, 101, 201),
+            Error::<Test>::PriceTooLow
+        );
+        assert_noop!(
+            KittiesModule::accept_offer(Origin::signed(100), kitty_id, 100, 0),
+            Error::<Test>::OfferNotFound
+        );
+
+        assert_ok!(KittiesModule::accept_offer(Origin::signed(100), kitty_id, 101, 200));
+
+        assert!(KittiesModule::kitties(&101, kitty_id).is_some());
+        assert_eq!(Balances::free_balance(100), 1200);
+        assert_eq!(Balances::free_balance(101), 800);
+        assert_eq!(Balances::reserved_balance(101), 0);
+        // the other offers are refunded and the listings cleared
+        assert_eq!(KittiesModule::offers(kitty_id, 102), None);
+        assert_eq!(Balances::reserved_balance(102), 0);
+        assert_eq!(Balances::free_balance(102), 1000);
+        assert_eq!(KittiesModule::kitty_prices(kitty_id), None);
+        assert_eq!(KittiesModule::sire_prices(kitty_id), None);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::OfferAccepted(
+            100, 101, kitty_id, 200,
+        )));
+    });
+}
+
+#[test]
+fn expired_offer_cannot_be_accepted() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::make_offer(Origin::signed(101), kitty_id, 200, 10));
+
+        run_to_block(10);
+        assert_noop!(
+            KittiesModule::accept_offer(Origin::signed(100), kitty_id, 101, 200),
+            Error::<Test>::OfferExpired
+        );
+
+        // the buyer still gets the funds back
+        assert_ok!(KittiesModule::withdraw_offer(Origin::signed(101), kitty_id));
+        assert_eq!(Balances::reserved_balance(101), 0);
+    });
+}
+
+#[test]
+fn offers_are_refunded_when_the_kitty_changes_hands() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::make_offer(Origin::signed(101), kitty_id, 100, 10));
+        assert_ok!(KittiesModule::make_offer(Origin::signed(102), kitty_id, 50, 10));
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 102, kitty_id));
+        assert_eq!(KittiesModule::offers(kitty_id, 101), None);
+        assert_eq!(KittiesModule::offers(kitty_id, 102), None);
+        assert_eq!(Balances::reserved_balance(101), 0);
+        assert_eq!(Balances::reserved_balance(102), 0);
+
+        assert_ok!(KittiesModule::make_offer(Origin::signed(100), kitty_id, 30, 10));
+        assert_ok!(KittiesModule::set_price(Origin::signed(102), kitty_id, Some(300)));
+        assert_ok!(KittiesModule::buy(Origin::signed(101), 102, kitty_id, 300));
+        assert_eq!(KittiesModule::offers(kitty_id, 100), None);
+        assert_eq!(Balances::reserved_balance(100), 0);
+    });
+}
+
+#[test]
+fn creator_is_recorded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_eq!(
+            KittiesModule::royalties((0, 0)),
+            Some(Royalty {
+                creator: 100,
+                rate: Perbill::zero(),
+            })
+        );
+
+        // the breeder is the creator of a bred kitty
+        let father = mint(100, MALE);
+        let mother = mint(100, FEMALE);
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, father));
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, mother));
+        let child = breed(101, mother, father);
+        assert_eq!(KittiesModule::royalties(child).map(|royalty| royalty.creator), Some(101));
+    });
+}
+
+#[test]
+fn can_set_royalty() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+
+        assert_noop!(
+            KittiesModule::set_royalty(Origin::signed(100), (0, 0), Perbill::from_percent(11)),
+            Error::<Test>::RoyaltyTooHigh
+        );
+        assert_noop!(
+            KittiesModule::set_royalty(Origin::signed(101), (0, 0), Perbill::from_percent(5)),
+            Error::<Test>::NotCreator
+        );
+        assert_noop!(
+            KittiesModule::set_royalty(Origin::signed(100), (0, 1), Perbill::from_percent(5)),
+            Error::<Test>::NotCreator
+        );
+
+        assert_ok!(KittiesModule::set_royalty(
+            Origin::signed(100),
+            (0, 0),
+            Perbill::from_percent(10)
+        ));
+        assert_eq!(KittiesModule::royalties((0, 0)).unwrap().rate, Perbill::from_percent(10));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::RoyaltySet(
+            100,
+            (0, 0),
+            Perbill::from_percent(10),
+        )));
+
+        // the creator keeps the royalty after the kitty changes hands, but can only lower it
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, (0, 0)));
+        assert_ok!(KittiesModule::set_royalty(
+            Origin::signed(100),
+            (0, 0),
+            Perbill::from_percent(5)
+        ));
+        assert_eq!(KittiesModule::royalties((0, 0)).unwrap().rate, Perbill::from_percent(5));
+        assert_noop!(
+            KittiesModule::set_royalty(Origin::signed(100), (0, 0), Perbill::from_percent(10)),
+            Error::<Test>::RoyaltyIncrease
+        );
+    });
+}
+
+#[test]
+fn royalty_is_paid_on_buy() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_ok!(KittiesModule::set_royalty(
+            Origin::signed(100),
+            (0, 0),
+            Perbill::from_percent(10)
+        ));
+
+        // no royalty when the creator sells
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), (0, 0), Some(100)));
+        assert_ok!(KittiesModule::buy(Origin::signed(101), 100, (0, 0), 100));
+        assert_eq!(Balances::free_balance(100), 1100);
+        assert_eq!(Balances::free_balance(101), 900);
+
+        assert_ok!(KittiesModule::set_price(Origin::signed(101), (0, 0), Some(200)));
+        assert_ok!(KittiesModule::buy(Origin::signed(102), 101, (0, 0), 200));
+        assert_eq!(Balances::free_balance(100), 1120);
+        assert_eq!(Balances::free_balance(101), 1080);
+        assert_eq!(Balances::free_balance(102), 800);
+        System::assert_has_event(Event::KittiesModule(crate::Event::<Test>::RoyaltyPaid(
+            100,
+            (0, 0),
+            20,
+        )));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittySold(
+            101,
+            102,
+            (0, 0),
+            200,
+        )));
+    });
+}
+
+#[test]
+fn royalty_is_skipped_when_a_reaped_creator_cannot_receive_it() {
+    new_test_ext().execute_with(|| {
+        ExistentialDeposit::set(50);
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_ok!(KittiesModule::set_royalty(
+            Origin::signed(100),
+            (0, 0),
+            Perbill::from_percent(10)
+        ));
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, (0, 0)));
+        assert_ok!(Balances::transfer(Origin::signed(100), 102, 1000));
+        assert!(!System::account_exists(&100));
+
+        // a royalty below the existential deposit goes to the seller
+        assert_ok!(KittiesModule::set_price(Origin::signed(101), (0, 0), Some(100)));
+        assert_ok!(KittiesModule::buy(Origin::signed(102), 101, (0, 0), 100));
+        assert_eq!(Balances::free_balance(101), 1100);
+        assert!(!System::account_exists(&100));
+
+        // a large enough royalty revives the creator
+        assert_ok!(KittiesModule::set_price(Origin::signed(102), (0, 0), Some(500)));
+        assert_ok!(KittiesModule::buy(Origin::signed(101), 102, (0, 0), 500));
+        assert_eq!(Balances::free_balance(100), 50);
+        assert_eq!(Balances::free_balance(102), 1900 + 450);
+    });
+}
+
+#[test]
+fn royalty_is_paid_on_auction_and_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_ok!(KittiesModule::set_royalty(
+            Origin::signed(100),
+            (0, 0),
+            Perbill::from_percent(10)
+        ));
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, (0, 0)));
+
+        assert_ok!(KittiesModule::create_auction(Origin::signed(101), (0, 0), 100, 20));
+        assert_ok!(KittiesModule::bid(Origin::signed(102), (0, 0), 150));
+        run_to_block(20);
+        assert!(KittiesModule::kitties(&102, (0, 0)).is_some());
+        assert_eq!(Balances::free_balance(100), 1015);
+        assert_eq!(Balances::free_balance(101), 1135);
+        assert_eq!(Balances::free_balance(102), 850);
+        assert_eq!(Balances::reserved_balance(102), 0);
+
+        assert_ok!(KittiesModule::make_offer(Origin::signed(101), (0, 0), 100, 30));
+        assert_ok!(KittiesModule::accept_offer(Origin::signed(102), (0, 0), 101, 100));
+        assert_eq!(Balances::free_balance(100), 1025);
+        assert_eq!(Balances::free_balance(101), 1035);
+        assert_eq!(Balances::reserved_balance(101), 0);
+        assert_eq!(Balances::free_balance(102), 940);
+        System::assert_has_event(Event::KittiesModule(crate::Event::<Test>::RoyaltyPaid(
+            100,
+            (0, 0),
+            10,
+        )));
+    });
+}
+
+#[test]
+fn can_set_marketplace_fee() {
+    new_test_ext().execute_with(|| {
+        // starts at the configured fee
+        assert_eq!(KittiesModule::marketplace_fee(), MarketplaceFee::get());
+        // and the fee account exists
+        assert_eq!(Balances::free_balance(KittiesModule::account_id()), 1);
+
+        assert_noop!(
+            KittiesModule::set_marketplace_fee(Origin::signed(100), Perbill::from_percent(5)),
+            DispatchError::BadOrigin
+        );
+
+        assert_ok!(KittiesModule::set_marketplace_fee(Origin::root(), Perbill::from_percent(5)));
+        assert_eq!(KittiesModule::marketplace_fee(), Perbill::from_percent(5));
+        System::assert_last_event(Event::KittiesModule(
+            crate::Event::<Test>::MarketplaceFeeSet(Perbill::from_percent(5)),
+        ));
+    });
+}
+
+#[test]
+fn balances_add_up_after_buy() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_ok!(KittiesModule::set_royalty(
+            Origin::signed(100),
+            (0, 0),
+            Perbill::from_percent(10)
+        ));
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, (0, 0)));
+        assert_ok!(KittiesModule::set_marketplace_fee(Origin::root(), Perbill::from_percent(5)));
+
+        let issuance = Balances::total_issuance();
+
+        assert_ok!(KittiesModule::set_price(Origin::signed(101), (0, 0), Some(200)));
+        assert_ok!(KittiesModule::buy(Origin::signed(102), 101, (0, 0), 200));
+
+        // 5% to the marketplace, 10% to the creator, the rest to the seller
+        assert_eq!(Balances::free_balance(KittiesModule::account_id()), 11);
+        assert_eq!(Balances::free_balance(100), 1020);
+        assert_eq!(Balances::free_balance(101), 1170);
+        assert_eq!(Balances::free_balance(102), 800);
+        assert_eq!(Balances::total_issuance(), issuance);
+        System::assert_has_event(Event::KittiesModule(
+            crate::Event::<Test>::MarketplaceFeePaid((0, 0), 10),
+        ));
+    });
+}
+
+#[test]
+fn marketplace_fee_is_taken_from_reserved_funds() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::set_marketplace_fee(Origin::root(), Perbill::from_percent(10)));
+
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id, 100, 20));
+        assert_ok!(KittiesModule::bid(Origin::signed(101), kitty_id, 100));
+        run_to_block(20);
+        assert_eq!(Balances::free_balance(KittiesModule::account_id()), 11);
+        assert_eq!(Balances::free_balance(100), 1090);
+        assert_eq!(Balances::reserved_balance(101), 0);
+
+        assert_ok!(KittiesModule::make_offer(Origin::signed(102), kitty_id, 200, 30));
+        assert_ok!(KittiesModule::accept_offer(Origin::signed(101), kitty_id, 102, 200));
+        assert_eq!(Balances::free_balance(KittiesModule::account_id()), 31);
+        assert_eq!(Balances::free_balance(101), 1080);
+        assert_eq!(Balances::free_balance(102), 800);
+        assert_eq!(Balances::reserved_balance(102), 0);
+    });
+}
+
+#[test]
+fn marketplace_fee_below_the_existential_deposit_does_not_fail_the_sale() {
+    new_test_ext().execute_with(|| {
+        ExistentialDeposit::set(500);
+        assert_ok!(KittiesModule::set_marketplace_fee(Origin::root(), Perbill::from_percent(2)));
+        let fee_account = KittiesModule::account_id();
+        let _ = <Balances as Currency<u64>>::slash(&fee_account, 1);
+        assert!(!System::account_exists(&fee_account));
+
+        // the fee can't create the account, so it is burnt
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), kitty_id, Some(400)));
+        assert_ok!(KittiesModule::buy(Origin::signed(101), 100, kitty_id, 400));
+        assert_eq!(Balances::free_balance(100), 1392);
+        assert_eq!(Balances::free_balance(101), 600);
+        assert!(!System::account_exists(&fee_account));
+        System::assert_has_event(Event::KittiesModule(
+            crate::Event::<Test>::MarketplaceFeePaid(kitty_id, 8),
+        ));
+
+        // a large enough fee creates it
+        Balances::make_free_balance_be(&102, 30_000);
+        assert_ok!(KittiesModule::set_price(Origin::signed(101), kitty_id, Some(25_000)));
+        assert_ok!(KittiesModule::buy(Origin::signed(102), 101, kitty_id, 25_000));
+        assert_eq!(Balances::free_balance(fee_account), 500);
+        assert_eq!(Balances::free_balance(101), 600 + 24_500);
+    });
+}
+
+#[test]
+fn marketplace_fee_and_royalty_never_exceed_the_price() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_ok!(KittiesModule::set_royalty(
+            Origin::signed(100),
+            (0, 0),
+            Perbill::from_percent(10)
+        ));
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, (0, 0)));
+        assert_ok!(KittiesModule::set_marketplace_fee(Origin::root(), Perbill::from_percent(95)));
+
+        assert_ok!(KittiesModule::set_price(Origin::signed(101), (0, 0), Some(100)));
+        assert_ok!(KittiesModule::buy(Origin::signed(102), 101, (0, 0), 100));
+
+        assert_eq!(Balances::free_balance(102), 900);
+        assert_eq!(Balances::free_balance(KittiesModule::account_id()), 96);
+        assert_eq!(Balances::free_balance(100), 1005);
+        assert_eq!(Balances::free_balance(101), 1000);
+    });
+}
+
+#[test]
+fn creating_kitties_reserves_a_deposit() {
+    new_test_ext().execute_with(|| {
+        KittyDeposit::set(10);
+
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_eq!(KittiesModule::kitty_deposits((0, 0)), Some(10));
+        assert_eq!(Balances::reserved_balance(100), 10);
+        assert_eq!(Balances::free_balance(100), 990);
+
+        // no funds, no kitty
+        assert_noop!(
+            KittiesModule::create_kitty(Origin::signed(103), 0),
+            pallet_balances::Error::<Test>::InsufficientBalance
+        );
+
+        // the breeder pays the deposit of the child
+        let father = mint(101, MALE);
+        let mother = mint(101, FEMALE);
+        let child = breed(101, mother, father);
+        assert_eq!(KittiesModule::kitty_deposits(child), Some(10));
+        assert_eq!(Balances::reserved_balance(101), 10);
+    });
+}
+
+#[test]
+fn kitty_deposit_moves_with_the_kitty() {
+    new_test_ext().execute_with(|| {
+        KittyDeposit::set(10);
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, (0, 0)));
+        assert_eq!(Balances::reserved_balance(100), 0);
+        assert_eq!(Balances::free_balance(100), 990);
+        assert_eq!(Balances::reserved_balance(101), 10);
+        assert_eq!(Balances::free_balance(101), 1000);
+
+        assert_ok!(KittiesModule::set_price(Origin::signed(101), (0, 0), Some(100)));
+        assert_ok!(KittiesModule::buy(Origin::signed(102), 101, (0, 0), 100));
+        assert_eq!(Balances::reserved_balance(101), 0);
+        assert_eq!(Balances::free_balance(101), 1100);
+        assert_eq!(Balances::reserved_balance(102), 10);
+        assert_eq!(Balances::free_balance(102), 900);
+    });
+}
+
+#[test]
+fn kitty_deposit_moves_to_a_new_account() {
+    new_test_ext().execute_with(|| {
+        KittyDeposit::set(10);
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 200, (0, 0)));
+        assert_eq!(Balances::reserved_balance(100), 10);
+        assert_eq!(Balances::reserved_balance(200), 10);
+        assert_eq!(Balances::free_balance(200), 0);
+
+        // a deposit below the existential deposit can't create the account
+        ExistentialDeposit::set(50);
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(100), 201, (0, 1)),
+            pallet_balances::Error::<Test>::ExistentialDeposit
+        );
+    });
+}
+
+#[test]
+fn listing_reserves_a_deposit() {
+    new_test_ext().execute_with(|| {
+        ListingDeposit::set(5);
+        let kitty_id = mint(100, MALE);
+
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), kitty_id, Some(50)));
+        assert_eq!(KittiesModule::listing_deposits(kitty_id), Some((100, 5)));
+        assert_eq!(Balances::reserved_balance(100), 5);
+
+        // changing the price doesn't take another deposit
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), kitty_id, Some(60)));
+        assert_eq!(Balances::reserved_balance(100), 5);
+
+        // refunded when delisted
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), kitty_id, None));
+        assert_eq!(KittiesModule::listing_deposits(kitty_id), None);
+        assert_eq!(Balances::reserved_balance(100), 0);
+
+        // or auctioned
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), kitty_id, Some(50)));
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id, 10, 20));
+        assert_eq!(Balances::reserved_balance(100), 0);
+        assert_ok!(KittiesModule::cancel_auction(Origin::signed(100), kitty_id));
+
+        // or sold
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), kitty_id, Some(50)));
+        assert_ok!(KittiesModule::buy(Origin::signed(101), 100, kitty_id, 50));
+        assert_eq!(KittiesModule::listing_deposits(kitty_id), None);
+        assert_eq!(Balances::reserved_balance(100), 0);
+        assert_eq!(Balances::free_balance(100), 1050);
+
+        // no funds, no listing
+        assert_ok!(KittiesModule::transfer(Origin::signed(101), 103, kitty_id));
+        assert_noop!(
+            KittiesModule::set_price(Origin::signed(103), kitty_id, Some(50)),
+            pallet_balances::Error::<Test>::InsufficientBalance
+        );
+    });
+}
+
+#[test]
+fn can_release_kitty() {
+    new_test_ext().execute_with(|| {
+        KittyDeposit::set(10);
+        ListingDeposit::set(5);
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), (0, 0), Some(50)));
+        assert_ok!(KittiesModule::make_offer(Origin::signed(101), (0, 0), 100, 10));
+        assert_eq!(Balances::reserved_balance(100), 15);
+
+        assert_noop!(
+            KittiesModule::release_kitty(Origin::signed(101), (0, 0)),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::release_kitty(Origin::signed(100), (0, 1)),
+            Error::<Test>::NotOwner
+        );
+
+        assert_ok!(KittiesModule::release_kitty(Origin::signed(100), (0, 0)));
+
+        assert_eq!(Nft::tokens(0, 0), None);
+        assert_eq!(KittiesModule::kitties(&100, (0, 0)), None);
+        assert_eq!(KittiesModule::kitty_prices((0, 0)), None);
+        assert_eq!(KittiesModule::kitty_deposits((0, 0)), None);
+        assert_eq!(KittiesModule::royalties((0, 0)), None);
+        assert_eq!(KittiesModule::offers((0, 0), 101), None);
+        // deposits and offers are refunded
+        assert_eq!(Balances::reserved_balance(100), 0);
+        assert_eq!(Balances::free_balance(100), 1000);
+        assert_eq!(Balances::reserved_balance(101), 0);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittyReleased(
+            100,
+            (0, 0),
+        )));
+    });
+}
+
+#[test]
+fn released_kitty_keeps_its_pedigree() {
+    new_test_ext().execute_with(|| {
+        let father = mint(100, MALE);
+        let mother = mint(100, FEMALE);
+        let daughter = breed(100, mother, father);
+
+        assert_ok!(KittiesModule::release_kitty(Origin::signed(100), mother));
+        assert!(KittiesModule::kitty_info(mother).is_some());
+        assert!(KittiesModule::are_related(daughter, mother));
+    });
+}
+
+#[test]
+fn cannot_release_auctioned_kitty() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), kitty_id, 10, 20));
+
+        assert_noop!(
+            KittiesModule::release_kitty(Origin::signed(100), kitty_id),
+            Error::<Test>::KittyInAuction
+        );
+    });
+}
+
+#[test]
+fn kitties_owned_are_counted() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_eq!(KittiesModule::kitties_owned(100), 2);
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, (0, 0)));
+        assert_eq!(KittiesModule::kitties_owned(100), 1);
+        assert_eq!(KittiesModule::kitties_owned(101), 1);
+
+        // sending to yourself changes nothing
+        assert_ok!(KittiesModule::transfer(Origin::signed(101), 101, (0, 0)));
+        assert_eq!(KittiesModule::kitties_owned(101), 1);
+
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), (0, 1), Some(10)));
+        assert_ok!(KittiesModule::buy(Origin::signed(101), 100, (0, 1), 10));
+        assert_eq!(KittiesModule::kitties_owned(100), 0);
+        assert_eq!(KittiesModule::kitties_owned(101), 2);
+
+        assert_ok!(KittiesModule::release_kitty(Origin::signed(101), (0, 1)));
+        assert_eq!(KittiesModule::kitties_owned(101), 1);
+    });
+}
+
+#[test]
+fn kitties_per_account_are_limited() {
+    new_test_ext().execute_with(|| {
+        MaxKittiesPerAccount::set(2);
+
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_noop!(
+            KittiesModule::create_kitty(Origin::signed(100), 0),
+            Error::<Test>::TooManyKittiesOwned
+        );
+
+        let father = mint(101, MALE);
+        let mother = mint(101, FEMALE);
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(101), mother, father),
+            Error::<Test>::TooManyKittiesOwned
+        );
+
+        // the recipient side of transfers and sales
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(100), 101, (0, 0)),
+            Error::<Test>::TooManyKittiesOwned
+        );
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), (0, 0), Some(10)));
+        assert_noop!(
+            KittiesModule::buy(Origin::signed(101), 100, (0, 0), 10),
+            Error::<Test>::TooManyKittiesOwned
+        );
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(101), 102, father));
+        assert_ok!(KittiesModule::buy(Origin::signed(101), 100, (0, 0), 10));
+    });
+}
+
+#[test]
+fn total_kitties_are_limited() {
+    new_test_ext().execute_with(|| {
+        MaxTotalKitties::set(Some(2));
+
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(101), 0));
+        assert_noop!(
+            KittiesModule::create_kitty(Origin::signed(102), 0),
+            Error::<Test>::MaxTotalKittiesReached
+        );
+
+        // released kitties make room
+        assert_ok!(KittiesModule::release_kitty(Origin::signed(100), (0, 0)));
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(102), 0));
+    });
+}
+
+#[test]
+fn migrate_to_v3_counts_kitties_owned() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::<Test>::put(Releases::V2);
+
+        Nft::mint(&100, KittiesModule::class_id(), Vec::new(), Kitty(MALE)).unwrap();
+        Nft::mint(&100, KittiesModule::class_id(), Vec::new(), Kitty(MALE)).unwrap();
+        Nft::mint(&101, KittiesModule::class_id(), Vec::new(), Kitty(FEMALE)).unwrap();
+
+        migrations::migrate_to_v3::<Test>();
+
+        assert_eq!(KittiesModule::kitties_owned(100), 2);
+        assert_eq!(KittiesModule::kitties_owned(101), 1);
+        assert_eq!(StorageVersion::<Test>::get(), Releases::V3);
+
+        // only runs once
+        migrations::migrate_to_v3::<Test>();
+        assert_eq!(KittiesModule::kitties_owned(100), 2);
+    });
+}
+
+#[test]
+fn can_name_kitty() {
+    new_test_ext().execute_with(|| {
+        NameDeposit::set(5);
+        let kitty_id = mint(100, MALE);
+
+        assert_noop!(
+            KittiesModule::set_kitty_name(Origin::signed(101), kitty_id, b"Tom".to_vec()),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::set_kitty_name(Origin::signed(100), kitty_id, vec![b'a'; 17]),
+            Error::<Test>::NameTooLong
+        );
+        assert_noop!(
+            KittiesModule::set_kitty_name(Origin::signed(100), kitty_id, vec![0xff, 0xfe]),
+            Error::<Test>::InvalidUtf8
+        );
+
+        assert_ok!(KittiesModule::set_kitty_name(Origin::signed(100), kitty_id, b"Tom".to_vec()));
+        assert_eq!(
+            KittiesModule::kitty_names(kitty_id),
+            Some(KittyName {
+                name: b"Tom".to_vec(),
+                deposit: 5,
+            })
+        );
+        assert_eq!(Balances::reserved_balance(100), 5);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittyRenamed(
+            100,
+            kitty_id,
+            b"Tom".to_vec(),
+        )));
+
+        // renaming keeps the deposit, multi byte characters are fine
+        let name = "Grüße 🐱".as_bytes().to_vec();
+        assert_ok!(KittiesModule::set_kitty_name(Origin::signed(100), kitty_id, name.clone()));
+        assert_eq!(KittiesModule::kitty_names(kitty_id).unwrap().name, name);
+        assert_eq!(Balances::reserved_balance(100), 5);
+
+        // an empty name clears it
+        assert_ok!(KittiesModule::set_kitty_name(Origin::signed(100), kitty_id, Vec::new()));
+        assert_eq!(KittiesModule::kitty_names(kitty_id), None);
+        assert_eq!(Balances::reserved_balance(100), 0);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittyRenamed(
+            100,
+            kitty_id,
+            Vec::new(),
+        )));
+    });
+}
+
+#[test]
+fn name_deposit_moves_with_the_kitty() {
+    new_test_ext().execute_with(|| {
+        NameDeposit::set(5);
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::set_kitty_name(Origin::signed(100), kitty_id, b"Tom".to_vec()));
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, kitty_id));
+        assert_eq!(KittiesModule::kitty_names(kitty_id).unwrap().name, b"Tom".to_vec());
+        assert_eq!(Balances::reserved_balance(100), 0);
+        assert_eq!(Balances::free_balance(100), 995);
+        assert_eq!(Balances::reserved_balance(101), 5);
+
+        // refunded on release
+        assert_ok!(KittiesModule::release_kitty(Origin::signed(101), kitty_id));
+        assert_eq!(KittiesModule::kitty_names(kitty_id), None);
+        assert_eq!(Balances::reserved_balance(101), 0);
+        assert_eq!(Balances::free_balance(101), 1005);
+    });
+}
+
+#[test]
+fn can_set_kitty_uri() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let uri = b"ipfs://kitty".to_vec();
+
+        assert_noop!(
+            KittiesModule::set_kitty_uri(Origin::signed(101), kitty_id, uri.clone()),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::set_kitty_uri(Origin::signed(100), (0, kitty_id.1 + 1), uri.clone()),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::set_kitty_uri(Origin::signed(100), kitty_id, vec![b'a'; 33]),
+            Error::<Test>::UriTooLong
+        );
+        assert_noop!(
+            KittiesModule::set_kitty_uri(Origin::signed(100), kitty_id, vec![0xff]),
+            Error::<Test>::InvalidUtf8
+        );
+
+        assert_ok!(KittiesModule::set_kitty_uri(Origin::signed(100), kitty_id, uri.clone()));
+        assert_eq!(
+            Nft::tokens(kitty_id.0, kitty_id.1).unwrap().metadata.to_vec(),
+            uri
+        );
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittyUriSet(
+            100, kitty_id, uri,
+        )));
+    });
+}
+
+#[test]
+fn can_create_collection() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            KittiesModule::create_collection(Origin::signed(100), Vec::new(), None, 0, None),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            KittiesModule::create_collection(Origin::root(), Vec::new(), None, 10, Some(10)),
+            Error::<Test>::InvalidMintWindow
+        );
+
+        let metadata = b"season 2".to_vec();
+        assert_ok!(KittiesModule::create_collection(
+            Origin::root(),
+            metadata.clone(),
+            Some(2),
+            5,
+            Some(10)
+        ));
+        assert_eq!(
+            KittiesModule::collections(1),
+            Some(Collection {
+                max_supply: Some(2),
+                mint_start: 5,
+                mint_end: Some(10),
+            })
+        );
+        assert_eq!(Nft::classes(1).unwrap().metadata.to_vec(), metadata);
+        System::assert_last_event(Event::KittiesModule(
+            crate::Event::<Test>::CollectionCreated(1),
+        ));
+    });
+}
+
+#[test]
+fn create_kitty_respects_the_mint_window_and_supply_cap() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create_collection(
+            Origin::root(),
+            Vec::new(),
+            Some(2),
+            5,
+            Some(10)
+        ));
+
+        assert_noop!(
+            KittiesModule::create_kitty(Origin::signed(100), 2),
+            Error::<Test>::CollectionNotFound
+        );
+        assert_noop!(
+            KittiesModule::create_kitty(Origin::signed(100), 1),
+            Error::<Test>::MintWindowClosed
+        );
+
+        System::set_block_number(5);
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 1));
+        assert!(KittiesModule::kitties(&100, (1, 0)).is_some());
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(101), 1));
+        assert_noop!(
+            KittiesModule::create_kitty(Origin::signed(102), 1),
+            Error::<Test>::CollectionSupplyReached
+        );
+
+        System::set_block_number(10);
+        assert_ok!(KittiesModule::release_kitty(Origin::signed(101), (1, 1)));
+        assert_noop!(
+            KittiesModule::create_kitty(Origin::signed(100), 1),
+            Error::<Test>::MintWindowClosed
+        );
+        // the genesis collection is still open
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+    });
+}
+
+#[test]
+fn bred_kitty_joins_the_collection_of_the_first_parent() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create_collection(Origin::root(), Vec::new(), Some(1), 0, None));
+        // the default random DNA is female
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 1));
+        let father = mint(100, MALE);
+
+        // the breeding is subject to the supply cap, but not to the mint window
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), (1, 0), father),
+            Error::<Test>::CollectionSupplyReached
+        );
+
+        let child = breed(100, father, (1, 0));
+        assert_eq!(child, (0, 1));
+        assert_eq!(KittiesModule::kitty_info(child).unwrap().parents, Some((father, (1, 0))));
+
+        // kitties of different collections trade independently
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, (1, 0)));
+        assert!(KittiesModule::kitties(&101, (1, 0)).is_some());
+        assert!(KittiesModule::kitties(&100, (0, 0)).is_some());
+    });
+}
+
+#[test]
+fn migrate_to_v4_keys_kitties_by_collection() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::<Test>::put(Releases::V3);
+        Collections::<Test>::remove(0);
+
+        let father = Nft::mint(&100, 0, Vec::new(), Kitty(MALE)).unwrap();
+        let mother = Nft::mint(&100, 0, Vec::new(), Kitty(FEMALE)).unwrap();
+        let child = Nft::mint(&101, 0, Vec::new(), Kitty(MALE)).unwrap();
+        put_v3_kitty_info(
+            child,
+            KittyInfo {
+                generation: 1,
+                parents: Some((mother, father)),
+                birth_block: 1,
+            },
+        );
+        let hashed = |kitty_id: u32| Blake2_128Concat::hash(&kitty_id.encode());
+        frame_support::storage::migration::put_storage_value(
+            b"KittiesModule",
+            b"KittyPrices",
+            &hashed(father),
+            50u64,
+        );
+        frame_support::storage::migration::put_storage_value(
+            b"KittiesModule",
+            b"Auctions",
+            &hashed(mother),
+            Auction::<u64, u64, u64> {
+                seller: 100,
+                reserve_price: 10,
+                end: 20,
+                best_bid: None,
+            },
+        );
+        frame_support::storage::migration::put_storage_value(
+            b"KittiesModule",
+            b"Offers",
+            &[hashed(child), Blake2_128Concat::hash(&102u64.encode())].concat(),
+            Offer::<u64, u64> {
+                amount: 30,
+                expiry: 10,
+            },
+        );
+
+        migrations::migrate_to_v4::<Test>();
+
+        assert_eq!(StorageVersion::<Test>::get(), Releases::V4);
+        assert_eq!(KittiesModule::kitty_prices((0, father)), Some(50));
+        assert_eq!(KittiesModule::auctions((0, mother)).unwrap().end, 20);
+        assert_eq!(
+            KittiesModule::kitty_info((0, child)).unwrap().parents,
+            Some(((0, mother), (0, father)))
+        );
+        assert_eq!(KittiesModule::offers((0, child), 102).unwrap().amount, 30);
+        assert!(KittiesModule::collections(0).is_some());
+        assert_eq!(KittiesModule::total_kitties(), 3);
+
+        // the auction still settles
+        run_to_block(20);
+        assert_eq!(KittiesModule::auctions((0, mother)), None);
+    });
+}
+
+#[test]
+fn migrate_to_v4_creates_a_missing_class() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::<Test>::put(Releases::V3);
+        Collections::<Test>::remove(0);
+        orml_nft::Classes::<Test>::remove(0);
+
+        migrations::migrate_to_v4::<Test>();
+
+        assert_eq!(StorageVersion::<Test>::get(), Releases::V4);
+        let class_id = KittiesModule::class_id();
+        assert_ne!(class_id, 0);
+        assert!(Nft::classes(class_id).is_some());
+        assert!(KittiesModule::collections(0).is_none());
+        assert!(KittiesModule::collections(class_id).is_some());
+        assert_eq!(KittiesModule::total_kitties(), 0);
+
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), class_id));
+        assert_eq!(KittiesModule::total_kitties(), 1);
+    });
+}
+
+#[test]
+fn approved_account_can_transfer_from() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+
+        assert_noop!(
+            KittiesModule::approve(Origin::signed(101), kitty_id, Some(102)),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::transfer_from(Origin::signed(102), 100, 103, kitty_id),
+            Error::<Test>::NotApproved
+        );
+
+        assert_ok!(KittiesModule::approve(Origin::signed(100), kitty_id, Some(102)));
+        assert_eq!(KittiesModule::approvals(kitty_id), Some(102));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::Approved(
+            100,
+            kitty_id,
+            Some(102),
+        )));
+
+        assert_noop!(
+            KittiesModule::transfer_from(Origin::signed(102), 101, 103, kitty_id),
+            Error::<Test>::NotOwner
+        );
+        assert_ok!(KittiesModule::transfer_from(Origin::signed(102), 100, 103, kitty_id));
+        assert!(KittiesModule::kitties(&103, kitty_id).is_some());
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittyTransferred(
+            100, 103, kitty_id,
+        )));
+
+        // the approval doesn't survive the change of hands
+        assert_eq!(KittiesModule::approvals(kitty_id), None);
+        assert_noop!(
+            KittiesModule::transfer_from(Origin::signed(102), 103, 100, kitty_id),
+            Error::<Test>::NotApproved
+        );
+
+        // and can be revoked
+        assert_ok!(KittiesModule::approve(Origin::signed(103), kitty_id, Some(102)));
+        assert_ok!(KittiesModule::approve(Origin::signed(103), kitty_id, None));
+        assert_eq!(KittiesModule::approvals(kitty_id), None);
+    });
+}
+
+#[test]
+fn operator_can_transfer_all_kitties_of_the_owner() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let other = mint(100, FEMALE);
+
+        assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(100), 102, true));
+        assert!(KittiesModule::operators(100, 102).is_some());
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::ApprovedForAll(
+            100, 102, true,
+        )));
+
+        assert_ok!(KittiesModule::transfer_from(Origin::signed(102), 100, 101, kitty_id));
+        assert_ok!(KittiesModule::transfer_from(Origin::signed(102), 100, 101, other));
+        // but not the kitties of other owners
+        assert_noop!(
+            KittiesModule::transfer_from(Origin::signed(102), 101, 100, kitty_id),
+            Error::<Test>::NotApproved
+        );
+
+        assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(101), 102, true));
+        assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(101), 102, false));
+        assert_noop!(
+            KittiesModule::transfer_from(Origin::signed(102), 101, 100, kitty_id),
+            Error::<Test>::NotApproved
+        );
+
+        // auctioned kitties are locked for operators too
+        assert_ok!(KittiesModule::create_auction(Origin::signed(101), other, 10, 20));
+        assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(101), 102, true));
+        assert_noop!(
+            KittiesModule::transfer_from(Origin::signed(102), 101, 100, other),
+            Error::<Test>::KittyInAuction
+        );
+    });
+}
+
+#[test]
+fn approvals_are_cleared_when_the_kitty_changes_hands() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::approve(Origin::signed(100), kitty_id, Some(102)));
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, kitty_id));
+        assert_eq!(KittiesModule::approvals(kitty_id), None);
+
+        assert_ok!(KittiesModule::approve(Origin::signed(101), kitty_id, Some(102)));
+        assert_ok!(KittiesModule::set_price(Origin::signed(101), kitty_id, Some(10)));
+        assert_ok!(KittiesModule::buy(Origin::signed(100), 101, kitty_id, 10));
+        assert_eq!(KittiesModule::approvals(kitty_id), None);
+
+        assert_ok!(KittiesModule::approve(Origin::signed(100), kitty_id, Some(102)));
+        assert_ok!(KittiesModule::release_kitty(Origin::signed(100), kitty_id));
+        assert_eq!(KittiesModule::approvals(kitty_id), None);
+    });
+}
+
+#[test]
+fn can_batch_transfer() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let other = mint(100, FEMALE);
+
+        assert_ok!(KittiesModule::batch_transfer(
+            Origin::signed(100),
+            vec![(101, kitty_id), (102, other)]
+        ));
+        assert_eq!(KittiesModule::kitties(&101, kitty_id), Some(Kitty(MALE)));
+        assert_eq!(KittiesModule::kitties(&102, other), Some(Kitty(FEMALE)));
+        assert_eq!(KittiesModule::kitties_owned(&100), 0);
+
+        assert_noop!(
+            KittiesModule::batch_transfer(Origin::signed(101), vec![(100, kitty_id); 4]),
+            Error::<Test>::BatchTooLarge
+        );
+    });
+}
+
+#[test]
+fn batch_transfer_is_all_or_nothing() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let other = mint(101, FEMALE);
+
+        // the second kitty is not ours, so the first one must not move either
+        assert_noop!(
+            KittiesModule::batch_transfer(
+                Origin::signed(100),
+                vec![(102, kitty_id), (102, other)]
+            ),
+            orml_nft::Error::<Test>::NoPermission
+        );
+        assert_eq!(KittiesModule::kitties(&100, kitty_id), Some(Kitty(MALE)));
+        assert_eq!(KittiesModule::kitties_owned(&102), 0);
+    });
+}
+
+#[test]
+fn can_batch_set_price() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let other = mint(100, FEMALE);
+        let foreign = mint(101, MALE);
+
+        assert_ok!(KittiesModule::batch_set_price(
+            Origin::signed(100),
+            vec![(kitty_id, Some(10)), (other, Some(20))]
+        ));
+        assert_eq!(KittiesModule::kitty_prices(kitty_id), Some(10));
+        assert_eq!(KittiesModule::kitty_prices(other), Some(20));
+
+        assert_noop!(
+            KittiesModule::batch_set_price(
+                Origin::signed(100),
+                vec![(kitty_id, None), (foreign, Some(5))]
+            ),
+            Error::<Test>::NotOwner
+        );
+        assert_eq!(KittiesModule::kitty_prices(kitty_id), Some(10));
+
+        assert_noop!(
+            KittiesModule::batch_set_price(Origin::signed(100), vec![(kitty_id, None); 4]),
+            Error::<Test>::BatchTooLarge
+        );
+    });
+}
+
+#[test]
+fn can_gift_a_kitty() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+
+        assert_ok!(KittiesModule::offer_transfer(Origin::signed(100), 101, kitty_id));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::TransferOffered(
+            100, 101, kitty_id, 11,
+        )));
+        // the kitty stays with the sender until accepted
+        assert_eq!(KittiesModule::kitties(&100, kitty_id), Some(Kitty(MALE)));
+
+        assert_noop!(
+            KittiesModule::accept_transfer(Origin::signed(102), kitty_id),
+            Error::<Test>::NotRecipient
+        );
+        assert_ok!(KittiesModule::accept_transfer(Origin::signed(101), kitty_id));
+        assert_eq!(KittiesModule::kitties(&101, kitty_id), Some(Kitty(MALE)));
+        assert_eq!(KittiesModule::pending_transfers(kitty_id), None);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittyTransferred(
+            100, 101, kitty_id,
+        )));
+
+        assert_noop!(
+            KittiesModule::accept_transfer(Origin::signed(101), kitty_id),
+            Error::<Test>::TransferNotFound
+        );
+    });
+}
+
+#[test]
+fn gifted_kitty_is_locked() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let other = mint(100, FEMALE);
+        assert_ok!(KittiesModule::offer_transfer(Origin::signed(100), 101, kitty_id));
+
+        assert_noop!(
+            KittiesModule::set_price(Origin::signed(100), kitty_id, Some(10)),
+            Error::<Test>::KittyPendingTransfer
+        );
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(100), 102, kitty_id),
+            Error::<Test>::KittyPendingTransfer
+        );
+        assert_noop!(
+            KittiesModule::offer_transfer(Origin::signed(100), 102, kitty_id),
+            Error::<Test>::KittyPendingTransfer
+        );
+        assert_noop!(
+            KittiesModule::create_auction(Origin::signed(100), kitty_id, 10, 20),
+            Error::<Test>::KittyPendingTransfer
+        );
+        assert_noop!(
+            KittiesModule::release_kitty(Origin::signed(100), kitty_id),
+            Error::<Test>::KittyPendingTransfer
+        );
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), kitty_id, other),
+            Error::<Test>::KittyPendingTransfer
+        );
+    });
+}
+
+#[test]
+fn gift_can_be_rejected_or_cancelled() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+
+        assert_ok!(KittiesModule::offer_transfer(Origin::signed(100), 101, kitty_id));
+        assert_noop!(
+            KittiesModule::reject_transfer(Origin::signed(100), kitty_id),
+            Error::<Test>::NotRecipient
+        );
+        assert_ok!(KittiesModule::reject_transfer(Origin::signed(101), kitty_id));
+        assert_eq!(KittiesModule::pending_transfers(kitty_id), None);
+        assert_eq!(KittiesModule::kitties(&100, kitty_id), Some(Kitty(MALE)));
+
+        assert_ok!(KittiesModule::offer_transfer(Origin::signed(100), 101, kitty_id));
+        assert_noop!(
+            KittiesModule::cancel_transfer(Origin::signed(101), kitty_id),
+            Error::<Test>::NotOwner
+        );
+        assert_ok!(KittiesModule::cancel_transfer(Origin::signed(100), kitty_id));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::TransferCancelled(
+            100, kitty_id,
+        )));
+
+        // the kitty is unlocked again
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 102, kitty_id));
+    });
+}
+
+#[test]
+fn gift_expires_after_the_timeout() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::offer_transfer(Origin::signed(100), 101, kitty_id));
+
+        run_to_block(10);
+        assert!(KittiesModule::pending_transfers(kitty_id).is_some());
+
+        run_to_block(11);
+        assert_eq!(KittiesModule::pending_transfers(kitty_id), None);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::TransferExpired(
+            kitty_id,
+        )));
+        assert_noop!(
+            KittiesModule::accept_transfer(Origin::signed(101), kitty_id),
+            Error::<Test>::TransferNotFound
+        );
+        assert_eq!(KittiesModule::kitties(&100, kitty_id), Some(Kitty(MALE)));
+    });
+}
+
+fn rental_terms(price_per_block: u64, max_duration: u64) -> RentalTerms<u64, u64> {
+    RentalTerms {
+        price_per_block,
+        max_duration,
+    }
+}
+
+#[test]
+fn can_rent_a_kitty() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+
+        assert_noop!(
+            KittiesModule::rent(Origin::signed(101), kitty_id, 5, 100),
+            Error::<Test>::NotForRent
+        );
+        assert_noop!(
+            KittiesModule::set_rental_terms(
+                Origin::signed(101),
+                kitty_id,
+                Some(rental_terms(2, 10))
+            ),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::set_rental_terms(
+                Origin::signed(100),
+                kitty_id,
+                Some(rental_terms(2, 0))
+            ),
+            Error::<Test>::InvalidRentalDuration
+        );
+        assert_ok!(KittiesModule::set_rental_terms(
+            Origin::signed(100),
+            kitty_id,
+            Some(rental_terms(2, 10))
+        ));
+
+        assert_noop!(
+            KittiesModule::rent(Origin::signed(100), kitty_id, 5, 100),
+            Error::<Test>::RentFromSelf
+        );
+        assert_noop!(
+            KittiesModule::rent(Origin::signed(101), kitty_id, 11, 100),
+            Error::<Test>::InvalidRentalDuration
+        );
+        assert_noop!(
+            KittiesModule::rent(Origin::signed(101), kitty_id, 5, 9),
+            Error::<Test>::PriceTooLow
+        );
+
+        assert_ok!(KittiesModule::rent(Origin::signed(101), kitty_id, 5, 10));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittyRented(
+            101, kitty_id, 6, 10,
+        )));
+        assert_eq!(Balances::free_balance(100), 1010);
+        assert_eq!(Balances::free_balance(101), 990);
+        // the owner keeps the kitty
+        assert_eq!(KittiesModule::kitties(&100, kitty_id), Some(Kitty(MALE)));
+
+        assert_noop!(
+            KittiesModule::rent(Origin::signed(102), kitty_id, 5, 10),
+            Error::<Test>::KittyRented
+        );
+    });
+}
+
+#[test]
+fn cannot_set_rental_terms_of_a_locked_kitty() {
+    new_test_ext().execute_with(|| {
+        let auctioned = mint(100, MALE);
+        let rented = mint(100, MALE);
+
+        assert_ok!(KittiesModule::create_auction(Origin::signed(100), auctioned, 100, 10));
+        assert_noop!(
+            KittiesModule::set_rental_terms(
+                Origin::signed(100),
+                auctioned,
+                Some(rental_terms(2, 10))
+            ),
+            Error::<Test>::KittyInAuction
+        );
+
+        assert_ok!(KittiesModule::set_rental_terms(
+            Origin::signed(100),
+            rented,
+            Some(rental_terms(2, 10))
+        ));
+        assert_ok!(KittiesModule::rent(Origin::signed(101), rented, 5, 10));
+        assert_noop!(
+            KittiesModule::set_rental_terms(Origin::signed(100), rented, Some(rental_terms(1, 10))),
+            Error::<Test>::KittyRented
+        );
+        assert_noop!(
+            KittiesModule::set_rental_terms(Origin::signed(100), rented, None),
+            Error::<Test>::KittyRented
+        );
+    });
+}
+
+#[test]
+fn renter_can_breed_but_not_sell_a_rented_kitty() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let female = mint(101, FEMALE);
+        let owners_female = mint(100, FEMALE);
+        assert_ok!(KittiesModule::set_rental_terms(
+            Origin::signed(100),
+            kitty_id,
+            Some(rental_terms(1, 10))
+        ));
+        assert_ok!(KittiesModule::rent(Origin::signed(101), kitty_id, 5, 5));
+
+        // the owner lost the breeding rights for now
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), kitty_id, owners_female),
+            Error::<Test>::InvalidKittyId
+        );
+        assert_ok!(KittiesModule::breed_kitties(Origin::signed(101), kitty_id, female));
+        // the child belongs to the renter
+        assert_eq!(KittiesModule::kitties_owned(&101), 2);
+
+        // the renter can offer it as a sire and gets the fee
+        assert_ok!(KittiesModule::set_sire_price(Origin::signed(101), kitty_id, Some(50)));
+        assert_noop!(
+            KittiesModule::set_sire_price(Origin::signed(100), kitty_id, Some(50)),
+            Error::<Test>::NotOwner
+        );
+
+        // but neither of them can sell it
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(101), 102, kitty_id),
+            Error::<Test>::KittyRented
+        );
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(100), 102, kitty_id),
+            Error::<Test>::KittyRented
+        );
+        assert_noop!(
+            KittiesModule::set_price(Origin::signed(100), kitty_id, Some(10)),
+            Error::<Test>::KittyRented
+        );
+        assert_noop!(
+            KittiesModule::release_kitty(Origin::signed(100), kitty_id),
+            Error::<Test>::KittyRented
+        );
+    });
+}
+
+#[test]
+fn rented_kitty_reverts_when_the_rental_ends() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let female = mint(100, FEMALE);
+        assert_ok!(KittiesModule::set_rental_terms(
+            Origin::signed(100),
+            kitty_id,
+            Some(rental_terms(1, 10))
+        ));
+        assert_ok!(KittiesModule::rent(Origin::signed(101), kitty_id, 5, 5));
+        assert_ok!(KittiesModule::set_sire_price(Origin::signed(101), kitty_id, Some(50)));
+
+        run_to_block(5);
+        assert!(KittiesModule::rentals(kitty_id).is_some());
+
+        run_to_block(6);
+        assert_eq!(KittiesModule::rentals(kitty_id), None);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::RentalEnded(
+            101, kitty_id,
+        )));
+        // the sire offer of the renter is gone, the terms stay
+        assert_eq!(KittiesModule::sire_prices(kitty_id), None);
+        assert_eq!(KittiesModule::rental_listings(kitty_id), Some(rental_terms(1, 10)));
+
+        assert_ok!(KittiesModule::breed_kitties(Origin::signed(100), kitty_id, female));
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 102, kitty_id));
+        // the terms don't carry over to the new owner
+        assert_eq!(KittiesModule::rental_listings(kitty_id), None);
+    });
+}
+
+#[test]
+fn can_fractionalize_a_kitty() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::set_price(Origin::signed(100), kitty_id, Some(10)));
+
+        assert_noop!(
+            KittiesModule::fractionalize(Origin::signed(101), kitty_id, 10, 100),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::fractionalize(Origin::signed(100), kitty_id, 0, 100),
+            Error::<Test>::InvalidShares
+        );
+
+        assert_ok!(KittiesModule::fractionalize(Origin::signed(100), kitty_id, 10, 100));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittyFractionalized(
+            100, kitty_id, 0, 10,
+        )));
+        // the vault holds the kitty, which is off the market
+        assert_eq!(
+            KittiesModule::kitties(&KittiesModule::vault_account(0), kitty_id),
+            Some(Kitty(MALE))
+        );
+        assert_ne!(KittiesModule::vault_account(0), KittiesModule::account_id());
+        assert_ne!(KittiesModule::vault_account(0), KittiesModule::vault_account(1));
+        assert_eq!(KittiesModule::kitty_prices(kitty_id), None);
+        assert_eq!(KittiesModule::kitties_owned(&100), 0);
+        assert_eq!(KittiesModule::kitty_vaults(kitty_id), Some(0));
+        assert_eq!(KittiesModule::shares(0, 100), 10);
+
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(100), 101, kitty_id),
+            orml_nft::Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn holder_of_all_shares_can_redeem() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::fractionalize(Origin::signed(100), kitty_id, 10, 100));
+
+        assert_noop!(
+            KittiesModule::transfer_shares(Origin::signed(100), 0, 101, 11),
+            Error::<Test>::InsufficientShares
+        );
+        assert_noop!(
+            KittiesModule::transfer_shares(Origin::signed(100), 1, 101, 1),
+            Error::<Test>::VaultNotFound
+        );
+        assert_ok!(KittiesModule::transfer_shares(Origin::signed(100), 0, 101, 4));
+        assert_ok!(KittiesModule::transfer_shares(Origin::signed(100), 0, 102, 6));
+        assert_eq!(KittiesModule::shares(0, 100), 0);
+        assert!(!Shares::<Test>::contains_key(0, 100));
+
+        assert_noop!(
+            KittiesModule::redeem(Origin::signed(101), 0),
+            Error::<Test>::InsufficientShares
+        );
+        assert_ok!(KittiesModule::transfer_shares(Origin::signed(102), 0, 101, 6));
+        assert_ok!(KittiesModule::redeem(Origin::signed(101), 0));
+
+        assert_eq!(KittiesModule::kitties(&101, kitty_id), Some(Kitty(MALE)));
+        assert_eq!(KittiesModule::kitties_owned(&101), 1);
+        assert_eq!(KittiesModule::vaults(0), None);
+        assert_eq!(KittiesModule::kitty_vaults(kitty_id), None);
+        assert_eq!(KittiesModule::shares(0, 101), 0);
+    });
+}
+
+#[test]
+fn kitty_can_be_bought_out_of_its_vault() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::fractionalize(Origin::signed(100), kitty_id, 10, 100));
+        assert_ok!(KittiesModule::transfer_shares(Origin::signed(100), 0, 101, 3));
+        assert_ok!(KittiesModule::transfer_shares(Origin::signed(100), 0, 102, 2));
+
+        // the buyer only pays for the shares of the others
+        assert_noop!(
+            KittiesModule::buyout(Origin::signed(102), 0, 79),
+            Error::<Test>::PriceTooLow
+        );
+        assert_ok!(KittiesModule::buyout(Origin::signed(102), 0, 80));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittyBoughtOut(
+            102, 0, 80,
+        )));
+        assert_eq!(KittiesModule::kitties(&102, kitty_id), Some(Kitty(MALE)));
+        assert_eq!(Balances::free_balance(102), 920);
+        assert_eq!(KittiesModule::kitty_vaults(kitty_id), None);
+
+        assert_noop!(
+            KittiesModule::buyout(Origin::signed(101), 0, 100),
+            Error::<Test>::VaultBoughtOut
+        );
+        assert_noop!(
+            KittiesModule::redeem(Origin::signed(101), 0),
+            Error::<Test>::VaultBoughtOut
+        );
+
+        assert_ok!(KittiesModule::claim_buyout(Origin::signed(100), 0));
+        assert_eq!(Balances::free_balance(100), 1050);
+        assert_noop!(
+            KittiesModule::claim_buyout(Origin::signed(100), 0),
+            Error::<Test>::InsufficientShares
+        );
+
+        // the last claim closes the vault
+        assert_ok!(KittiesModule::claim_buyout(Origin::signed(101), 0));
+        assert_eq!(Balances::free_balance(101), 1030);
+        assert_eq!(KittiesModule::vaults(0), None);
+
+        // the kitty can be fractionalized again
+        assert_ok!(KittiesModule::fractionalize(Origin::signed(102), kitty_id, 5, 100));
+        assert_eq!(KittiesModule::kitty_vaults(kitty_id), Some(1));
+    });
+}
+
+#[test]
+fn buyout_pays_the_marketplace_fee_and_the_royalty() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create_kitty(Origin::signed(100), 0));
+        assert_ok!(KittiesModule::set_royalty(
+            Origin::signed(100),
+            (0, 0),
+            Perbill::from_percent(10)
+        ));
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, (0, 0)));
+        assert_ok!(KittiesModule::set_marketplace_fee(Origin::root(), Perbill::from_percent(5)));
+
+        assert_ok!(KittiesModule::fractionalize(Origin::signed(101), (0, 0), 10, 200));
+        assert_ok!(KittiesModule::buyout(Origin::signed(102), 0, 200));
+        assert_eq!(Balances::free_balance(102), 800);
+        assert_eq!(Balances::free_balance(KittiesModule::account_id()), 1 + 10);
+        assert_eq!(Balances::free_balance(100), 1020);
+        assert_eq!(Balances::free_balance(KittiesModule::vault_account(0)), 170);
+
+        // the holders share what the vault received
+        assert_ok!(KittiesModule::transfer_shares(Origin::signed(101), 0, 100, 3));
+        assert_ok!(KittiesModule::claim_buyout(Origin::signed(100), 0));
+        assert_eq!(Balances::free_balance(100), 1020 + 51);
+        assert_ok!(KittiesModule::claim_buyout(Origin::signed(101), 0));
+        assert_eq!(Balances::free_balance(101), 1000 + 119);
+        assert!(!System::account_exists(&KittiesModule::vault_account(0)));
+    });
+}
+
+#[test]
+fn claim_buyout_needs_a_bought_out_vault() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::fractionalize(Origin::signed(100), kitty_id, 10, 100));
+
+        assert_noop!(
+            KittiesModule::claim_buyout(Origin::signed(100), 0),
+            Error::<Test>::NotBoughtOut
+        );
+        assert_noop!(
+            KittiesModule::claim_buyout(Origin::signed(100), 1),
+            Error::<Test>::VaultNotFound
+        );
+    });
+}
+
+#[test]
+fn staked_kitty_is_locked() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let female = mint(100, FEMALE);
+
+        assert_noop!(
+            KittiesModule::stake_kitty(Origin::signed(101), kitty_id),
+            Error::<Test>::NotOwner
+        );
+        assert_ok!(KittiesModule::stake_kitty(Origin::signed(100), kitty_id));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::KittyStaked(
+            100, kitty_id,
+        )));
+
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(100), 101, kitty_id),
+            Error::<Test>::KittyStaked
+        );
+        assert_noop!(
+            KittiesModule::set_price(Origin::signed(100), kitty_id, Some(10)),
+            Error::<Test>::KittyStaked
+        );
+        assert_noop!(
+            KittiesModule::breed_kitties(Origin::signed(100), kitty_id, female),
+            Error::<Test>::KittyStaked
+        );
+        assert_noop!(
+            KittiesModule::stake_kitty(Origin::signed(100), kitty_id),
+            Error::<Test>::KittyStaked
+        );
+
+        assert_noop!(
+            KittiesModule::unstake_kitty(Origin::signed(101), kitty_id),
+            Error::<Test>::NotStaked
+        );
+        assert_ok!(KittiesModule::unstake_kitty(Origin::signed(100), kitty_id));
+        assert_eq!(KittiesModule::staked_kitties(kitty_id), None);
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, kitty_id));
+    });
+}
+
+#[test]
+fn staked_kitty_earns_xp_and_rewards() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        assert_ok!(KittiesModule::fund_rewards(Origin::signed(102), 101));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::RewardPotFunded(
+            102, 101,
+        )));
+        assert_eq!(Balances::free_balance(KittiesModule::reward_pot()), 101);
+
+        assert_ok!(KittiesModule::stake_kitty(Origin::signed(100), kitty_id));
+
+        System::set_block_number(11);
+        assert_ok!(KittiesModule::claim_rewards(Origin::signed(100), kitty_id));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::StakingRewarded(
+            100, kitty_id, 30, 20,
+        )));
+        assert_eq!(KittiesModule::kitty_xp(kitty_id), 30);
+        assert_eq!(Balances::free_balance(100), 1020);
+        // still staked
+        assert!(KittiesModule::staked_kitties(kitty_id).is_some());
+
+        // the pot pays what it has left, the XP keeps growing
+        System::set_block_number(61);
+        assert_ok!(KittiesModule::unstake_kitty(Origin::signed(100), kitty_id));
+        assert_eq!(KittiesModule::kitty_xp(kitty_id), 180);
+        assert_eq!(Balances::free_balance(100), 1100);
+        assert_eq!(Balances::free_balance(KittiesModule::reward_pot()), 1);
+
+        // the XP stays with the kitty
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 101, kitty_id));
+        assert_eq!(KittiesModule::kitty_xp(kitty_id), 180);
+    });
+}
+
+// the lowest alleles and no accessories give the lowest power, [0xff; 16] has the highest
+const WEAK: [u8; 16] = [0, 0, 0, 0, 0, 0, 0, 0, 0x11, 0x11, 0x11, 0x11, 0, 0, 0, 0];
+const STRONG: [u8; 16] = [0xff; 16];
+
+fn roll(value: u32) -> [u8; 16] {
+    let mut random = [0u8; 16];
+    random[..4].copy_from_slice(&value.to_le_bytes());
+    random
+}
+
+#[test]
+fn battle_power_comes_from_the_phenotype() {
+    use battle::*;
+
+    // every trait adds one
+    assert_eq!(power(&Kitty(WEAK)), 7);
+    // every hat adds the accessory bonus
+    assert_eq!(power(&Kitty([0; 16])), 7 + 4 * ACCESSORY_BONUS);
+    // the highest alleles but no accessories
+    assert_eq!(power(&Kitty(STRONG)), 7 * 16);
+}
+
+#[test]
+fn resolve_battle_is_decided_by_the_share_of_power() {
+    use battle::*;
+
+    let weak = Kitty(WEAK);
+    let strong = Kitty(STRONG);
+    let total = power(&weak) + power(&strong);
+
+    assert_eq!(resolve_battle(&weak, &strong, &roll(0)), Winner::Challenger);
+    assert_eq!(resolve_battle(&weak, &strong, &roll(power(&weak) - 1)), Winner::Challenger);
+    assert_eq!(resolve_battle(&weak, &strong, &roll(power(&weak))), Winner::Opponent);
+    assert_eq!(resolve_battle(&weak, &strong, &roll(total - 1)), Winner::Opponent);
+    // the roll wraps around
+    assert_eq!(resolve_battle(&weak, &strong, &roll(total)), Winner::Challenger);
+
+    // the same inputs always give the same winner
+    for value in [7u32, 1234, u32::MAX].iter() {
+        assert_eq!(
+            resolve_battle(&strong, &weak, &roll(*value)),
+            resolve_battle(&strong, &weak, &roll(*value))
+        );
+    }
+}
+
+#[test]
+fn can_challenge_and_cancel() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let opponent_id = mint(101, FEMALE);
+
+        assert_noop!(
+            KittiesModule::challenge(Origin::signed(101), kitty_id, opponent_id, 10),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::challenge(Origin::signed(100), kitty_id, kitty_id, 10),
+            Error::<Test>::CannotBattleSelf
+        );
+        assert_noop!(
+            KittiesModule::challenge(Origin::signed(100), kitty_id, (0, 9), 10),
+            Error::<Test>::InvalidKittyId
+        );
+        assert_noop!(
+            KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 4),
+            Error::<Test>::ChallengeStakeTooLow
+        );
+
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 10));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::ChallengeIssued(
+            100,
+            kitty_id,
+            opponent_id,
+            10,
+            21,
+        )));
+        assert_eq!(Balances::reserved_balance(100), 10);
+        assert_noop!(
+            KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 10),
+            Error::<Test>::ChallengeExists
+        );
+
+        assert_noop!(
+            KittiesModule::cancel_challenge(Origin::signed(101), kitty_id),
+            Error::<Test>::ChallengeNotFound
+        );
+        assert_ok!(KittiesModule::cancel_challenge(Origin::signed(100), kitty_id));
+        assert_eq!(Balances::reserved_balance(100), 0);
+        assert_eq!(KittiesModule::challenges(kitty_id), None);
+        assert_eq!(ScheduledCount::<Test>::get(21), 0);
+
+        // a transfer withdraws the challenge too
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 10));
+        assert_ok!(KittiesModule::transfer(Origin::signed(100), 102, kitty_id));
+        assert_eq!(KittiesModule::challenges(kitty_id), None);
+        assert_eq!(Balances::reserved_balance(100), 0);
+    });
+}
+
+#[test]
+fn user_of_the_challenged_kitty_can_reject_a_challenge() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let opponent_id = mint(101, FEMALE);
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 10));
+
+        assert_noop!(
+            KittiesModule::reject_challenge(Origin::signed(102), kitty_id),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::reject_challenge(Origin::signed(100), kitty_id),
+            Error::<Test>::NotOwner
+        );
+        assert_ok!(KittiesModule::reject_challenge(Origin::signed(101), kitty_id));
+        System::assert_last_event(Event::KittiesModule(
+            crate::Event::<Test>::ChallengeRejected(101, kitty_id),
+        ));
+        assert_eq!(KittiesModule::challenges(kitty_id), None);
+        assert_eq!(Balances::reserved_balance(100), 0);
+        assert_eq!(ChallengesAgainst::<Test>::iter().count(), 0);
+        assert_eq!(ChallengeExpiry::<Test>::iter().count(), 0);
+        assert_noop!(
+            KittiesModule::reject_challenge(Origin::signed(101), kitty_id),
+            Error::<Test>::ChallengeNotFound
+        );
+
+        // the renter decides while the kitty is rented out
+        assert_ok!(KittiesModule::set_rental_terms(
+            Origin::signed(101),
+            opponent_id,
+            Some(rental_terms(1, 10))
+        ));
+        assert_ok!(KittiesModule::rent(Origin::signed(102), opponent_id, 5, 5));
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 10));
+        assert_noop!(
+            KittiesModule::reject_challenge(Origin::signed(101), kitty_id),
+            Error::<Test>::NotOwner
+        );
+        assert_ok!(KittiesModule::reject_challenge(Origin::signed(102), kitty_id));
+        assert_eq!(Balances::reserved_balance(100), 0);
+    });
+}
+
+#[test]
+fn unaccepted_challenge_expires() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let opponent_id = mint(101, FEMALE);
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 10));
+        assert_eq!(KittiesModule::challenges(kitty_id).unwrap().expiry, 21);
+
+        run_to_block(20);
+        assert!(KittiesModule::challenges(kitty_id).is_some());
+        assert_eq!(Balances::reserved_balance(100), 10);
+
+        run_to_block(21);
+        assert_eq!(KittiesModule::challenges(kitty_id), None);
+        assert_eq!(Balances::reserved_balance(100), 0);
+        assert_eq!(ChallengesAgainst::<Test>::iter().count(), 0);
+        assert_eq!(ScheduledCount::<Test>::iter().count(), 0);
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::ChallengeExpired(
+            100, kitty_id,
+        )));
+        assert_noop!(
+            KittiesModule::accept_challenge(Origin::signed(101), kitty_id),
+            Error::<Test>::ChallengeNotFound
+        );
+
+        // an accepted challenge no longer expires
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 10));
+        assert_ok!(KittiesModule::accept_challenge(Origin::signed(101), kitty_id));
+        assert_eq!(ChallengeExpiry::<Test>::iter().count(), 0);
+        assert_eq!(ScheduledCount::<Test>::get(41), 0);
+    });
+}
+
+#[test]
+fn challenges_against_a_kitty_are_limited_and_withdrawn_when_it_changes_hands() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let other_id = mint(102, MALE);
+        let opponent_id = mint(101, FEMALE);
+
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 10));
+        assert_ok!(KittiesModule::challenge(Origin::signed(102), other_id, opponent_id, 10));
+        let third_id = mint(100, MALE);
+        assert_noop!(
+            KittiesModule::challenge(Origin::signed(100), third_id, opponent_id, 10),
+            Error::<Test>::TooManyChallenges
+        );
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(101), 102, opponent_id));
+        assert_eq!(KittiesModule::challenges(kitty_id), None);
+        assert_eq!(KittiesModule::challenges(other_id), None);
+        assert_eq!(Balances::reserved_balance(100), 0);
+        assert_eq!(Balances::reserved_balance(102), 0);
+        assert_eq!(ChallengesAgainst::<Test>::iter().count(), 0);
+
+        // releasing a kitty withdraws the challenges against it too
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 10));
+        assert_ok!(KittiesModule::release_kitty(Origin::signed(102), opponent_id));
+        assert_eq!(KittiesModule::challenges(kitty_id), None);
+        assert_eq!(Balances::reserved_balance(100), 0);
+    });
+}
+
+#[test]
+fn challenges_made_with_a_rented_kitty_end_with_the_rental() {
+    new_test_ext().execute_with(|| {
+        let rented_id = mint(100, MALE);
+        let target_id = mint(102, FEMALE);
+
+        // renting withdraws the challenges of the owner
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), rented_id, target_id, 10));
+        assert_ok!(KittiesModule::set_rental_terms(
+            Origin::signed(100),
+            rented_id,
+            Some(rental_terms(1, 10))
+        ));
+        assert_ok!(KittiesModule::rent(Origin::signed(101), rented_id, 5, 5));
+        assert_eq!(KittiesModule::challenges(rented_id), None);
+        assert_eq!(Balances::reserved_balance(100), 0);
+
+        // the renter challenges with the kitty and gets challenged
+        assert_ok!(KittiesModule::challenge(Origin::signed(101), rented_id, target_id, 10));
+        assert_ok!(KittiesModule::challenge(Origin::signed(102), target_id, rented_id, 10));
+
+        run_to_block(6);
+        assert_eq!(KittiesModule::rentals(rented_id), None);
+        assert_eq!(KittiesModule::challenges(rented_id), None);
+        assert_eq!(KittiesModule::challenges(target_id), None);
+        assert_eq!(Balances::reserved_balance(101), 0);
+        assert_eq!(Balances::reserved_balance(102), 0);
+    });
+}
+
+#[test]
+fn winner_of_a_battle_takes_the_stake() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let opponent_id = mint(101, FEMALE);
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 10));
+
+        assert_noop!(
+            KittiesModule::accept_challenge(Origin::signed(102), kitty_id),
+            Error::<Test>::NotOwner
+        );
+        assert_ok!(KittiesModule::accept_challenge(Origin::signed(101), kitty_id));
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::ChallengeAccepted(
+            101,
+            kitty_id,
+            opponent_id,
+            2,
+        )));
+        assert_eq!(Balances::reserved_balance(100), 10);
+        assert_eq!(Balances::reserved_balance(101), 10);
+        assert_eq!(KittiesModule::challenges(kitty_id), None);
+
+        // the battle is fought with the randomness of the next block
+        MockRandom::set(H256::from([7; 32]));
+        run_to_block(2);
+        assert_eq!(KittiesModule::pending_battles(2, kitty_id), None);
+
+        let winner = battle::resolve_battle(
+            &Kitty(MALE),
+            &Kitty(FEMALE),
+            &KittiesModule::battle_random(kitty_id),
+        );
+        let (winner, winner_id, loser, loser_id) = match winner {
+            battle::Winner::Challenger => (100, kitty_id, 101, opponent_id),
+            battle::Winner::Opponent => (101, opponent_id, 100, kitty_id),
+        };
+        System::assert_last_event(Event::KittiesModule(crate::Event::<Test>::BattleFought(
+            winner, winner_id, loser_id, 10,
+        )));
+        assert_eq!(Balances::free_balance(winner), 1010);
+        assert_eq!(Balances::free_balance(loser), 990);
+        assert_eq!(Balances::reserved_balance(winner), 0);
+        assert_eq!(Balances::reserved_balance(loser), 0);
+        assert_eq!(KittiesModule::challenges(kitty_id), None);
+
+        assert_eq!(
+            KittiesModule::battle_history(winner_id).to_vec(),
+            vec![BattleRecord {
+                opponent: loser_id,
+                won: true,
+                block: 2
+            }]
+        );
+        assert_eq!(
+            KittiesModule::battle_history(loser_id).to_vec(),
+            vec![BattleRecord {
+                opponent: winner_id,
+                won: false,
+                block: 2
+            }]
+        );
+    });
+}
+
+#[test]
+fn battle_is_off_when_a_kitty_is_released_before_the_fight() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let opponent_id = mint(101, FEMALE);
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 10));
+        assert_ok!(KittiesModule::accept_challenge(Origin::signed(101), kitty_id));
+
+        assert_ok!(KittiesModule::release_kitty(Origin::signed(101), opponent_id));
+        run_to_block(2);
+
+        assert_eq!(Balances::free_balance(100), 1000);
+        assert_eq!(Balances::free_balance(101), 1000);
+        assert_eq!(Balances::reserved_balance(100), 0);
+        assert_eq!(Balances::reserved_balance(101), 0);
+        assert!(KittiesModule::battle_history(kitty_id).is_empty());
+        System::assert_last_event(Event::KittiesModule(
+            crate::Event::<Test>::ChallengeCancelled(100, kitty_id),
+        ));
+    });
+}
+
+#[test]
+fn battles_have_a_cooldown_and_a_bounded_history() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = mint(100, MALE);
+        let opponent_id = mint(101, FEMALE);
+
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 5));
+        assert_ok!(KittiesModule::accept_challenge(Origin::signed(101), kitty_id));
+        // the cooldown starts with the battle in the next block
+        assert_eq!(KittiesModule::battle_ready_at(kitty_id), 7);
+        assert_eq!(KittiesModule::battle_ready_at(opponent_id), 7);
+
+        assert_noop!(
+            KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 5),
+            Error::<Test>::KittyOnBattleCooldown
+        );
+        // the opponent must be rested too
+        let other = mint(100, MALE);
+        assert_ok!(KittiesModule::challenge(Origin::signed(100), other, opponent_id, 5));
+        assert_noop!(
+            KittiesModule::accept_challenge(Origin::signed(101), other),
+            Error::<Test>::KittyOnBattleCooldown
+        );
+        assert_ok!(KittiesModule::cancel_challenge(Origin::signed(100), other));
+
+        for block in [7, 13].iter() {
+            run_to_block(*block);
+            assert_ok!(KittiesModule::challenge(Origin::signed(100), kitty_id, opponent_id, 5));
+            assert_ok!(KittiesModule::accept_challenge(Origin::signed(101), kitty_id));
+        }
+        run_to_block(14);
+
+        // only the two latest battles are kept
+        let history = KittiesModule::battle_history(kitty_id);
+        assert_eq!(history.iter().map(|record| record.block).collect::<Vec<_>>(), vec![8, 14]);
+        assert_eq!(KittiesModule::battle_history(opponent_id).len(), 2);
     });
 }
@@ -0,0 +1,625 @@
+//! Weights for pallet_kitties
+//!
+//! PLACEHOLDER VALUES, NOT BENCHMARK OUTPUT: the numbers below are estimates that have not been
+//! measured yet. Replace this whole file with the output of `scripts/benchmark.sh`, which runs the
+//! benchmarks in `benchmarking.rs` through `.maintain/frame-weight-template.hbs`:
+
+// ./target/release/node-template
+// benchmark
+// --chain=dev
+// --execution=wasm
+// --wasm-execution=compiled
+// --pallet=pallet_kitties
+// --extrinsic=*
+// --steps=50
+// --repeat=20
+// --heap-pages=4096
+// --template=./.maintain/frame-weight-template.hbs
+// --output=./pallets/kitties/src/weights.rs
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_kitties.
+pub trait WeightInfo {
+    fn create_kitty() -> Weight;
+    fn breed_kitties() -> Weight;
+    fn transfer(o: u32, c: u32) -> Weight;
+    fn set_price() -> Weight;
+    fn buy(o: u32) -> Weight;
+    fn set_sire_price() -> Weight;
+    fn breed_with_sire() -> Weight;
+    fn create_auction() -> Weight;
+    fn bid() -> Weight;
+    fn cancel_auction() -> Weight;
+    fn create_dutch_auction() -> Weight;
+    fn buy_dutch(o: u32) -> Weight;
+    fn cancel_dutch_auction() -> Weight;
+    fn make_offer(o: u32) -> Weight;
+    fn withdraw_offer() -> Weight;
+    fn accept_offer(o: u32) -> Weight;
+    fn set_royalty() -> Weight;
+    fn release_kitty(o: u32, c: u32) -> Weight;
+    fn set_kitty_name() -> Weight;
+    fn set_kitty_uri() -> Weight;
+    fn set_marketplace_fee() -> Weight;
+    fn create_collection() -> Weight;
+    fn approve() -> Weight;
+    fn set_approval_for_all() -> Weight;
+    fn transfer_from(o: u32) -> Weight;
+    fn batch_transfer(n: u32) -> Weight;
+    fn batch_set_price(n: u32) -> Weight;
+    fn offer_transfer() -> Weight;
+    fn accept_transfer(o: u32) -> Weight;
+    fn reject_transfer() -> Weight;
+    fn cancel_transfer() -> Weight;
+    fn set_rental_terms() -> Weight;
+    fn rent() -> Weight;
+    fn fractionalize(o: u32) -> Weight;
+    fn transfer_shares() -> Weight;
+    fn redeem() -> Weight;
+    fn buyout() -> Weight;
+    fn claim_buyout() -> Weight;
+    fn stake_kitty() -> Weight;
+    fn unstake_kitty() -> Weight;
+    fn claim_rewards() -> Weight;
+    fn fund_rewards() -> Weight;
+    fn challenge() -> Weight;
+    fn cancel_challenge() -> Weight;
+    fn reject_challenge() -> Weight;
+    fn accept_challenge() -> Weight;
+    fn on_initialize(n: u32) -> Weight;
+}
+
+/// Weights for pallet_kitties using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn create_kitty() -> Weight {
+        (51_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+    fn breed_kitties() -> Weight {
+        (75_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(15 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+    }
+    fn transfer(o: u32, c: u32) -> Weight {
+        (38_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add((24_000_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(T::DbWeight::get().writes(16 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().writes((5 as Weight).saturating_mul(c as Weight)))
+    }
+    fn set_price() -> Weight {
+        (24_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn buy(o: u32) -> Weight {
+        (91_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(17 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().writes(16 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn set_sire_price() -> Weight {
+        (26_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn breed_with_sire() -> Weight {
+        (122_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(18 as Weight))
+            .saturating_add(T::DbWeight::get().writes(14 as Weight))
+    }
+    fn create_auction() -> Weight {
+        (33_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn bid() -> Weight {
+        (61_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn cancel_auction() -> Weight {
+        (27_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn create_dutch_auction() -> Weight {
+        (34_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn buy_dutch(o: u32) -> Weight {
+        (88_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(15 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().writes(15 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn cancel_dutch_auction() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn make_offer(o: u32) -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn withdraw_offer() -> Weight {
+        (36_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn accept_offer(o: u32) -> Weight {
+        (105_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(16 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().writes(16 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn set_royalty() -> Weight {
+        (23_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn release_kitty(o: u32, c: u32) -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add((24_000_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(T::DbWeight::get().writes(19 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().writes((5 as Weight).saturating_mul(c as Weight)))
+    }
+    fn set_kitty_name() -> Weight {
+        (41_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_kitty_uri() -> Weight {
+        (29_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_marketplace_fee() -> Weight {
+        (14_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn create_collection() -> Weight {
+        (31_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn approve() -> Weight {
+        (22_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_approval_for_all() -> Weight {
+        (17_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn transfer_from(o: u32) -> Weight {
+        (44_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn batch_transfer(n: u32) -> Weight {
+        (21_000_000 as Weight)
+            .saturating_add((418_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads((30 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes((52 as Weight).saturating_mul(n as Weight)))
+    }
+    fn batch_set_price(n: u32) -> Weight {
+        (19_000_000 as Weight)
+            .saturating_add((24_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads((6 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+    }
+    fn offer_transfer() -> Weight {
+        (33_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn accept_transfer(o: u32) -> Weight {
+        (47_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().writes(14 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn reject_transfer() -> Weight {
+        (26_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn cancel_transfer() -> Weight {
+        (26_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_rental_terms() -> Weight {
+        (24_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn rent() -> Weight {
+        (62_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    fn fractionalize(o: u32) -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn transfer_shares() -> Weight {
+        (28_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn redeem() -> Weight {
+        (46_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
+    }
+    fn buyout() -> Weight {
+        (84_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(13 as Weight))
+    }
+    fn claim_buyout() -> Weight {
+        (48_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn stake_kitty() -> Weight {
+        (27_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn unstake_kitty() -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn claim_rewards() -> Weight {
+        (56_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn fund_rewards() -> Weight {
+        (41_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn challenge() -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn cancel_challenge() -> Weight {
+        (31_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn reject_challenge() -> Weight {
+        (36_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn accept_challenge() -> Weight {
+        (64_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    fn on_initialize(n: u32) -> Weight {
+        (2_100_000 as Weight)
+            .saturating_add((450_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads((56 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes((56 as Weight).saturating_mul(n as Weight)))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn create_kitty() -> Weight {
+        (51_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+    fn breed_kitties() -> Weight {
+        (75_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(15 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+    }
+    fn transfer(o: u32, c: u32) -> Weight {
+        (38_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add((24_000_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(16 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(c as Weight)))
+    }
+    fn set_price() -> Weight {
+        (24_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn buy(o: u32) -> Weight {
+        (91_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(17 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(16 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn set_sire_price() -> Weight {
+        (26_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn breed_with_sire() -> Weight {
+        (122_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(18 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(14 as Weight))
+    }
+    fn create_auction() -> Weight {
+        (33_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn bid() -> Weight {
+        (61_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn cancel_auction() -> Weight {
+        (27_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn create_dutch_auction() -> Weight {
+        (34_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn buy_dutch(o: u32) -> Weight {
+        (88_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(15 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(15 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn cancel_dutch_auction() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn make_offer(o: u32) -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn withdraw_offer() -> Weight {
+        (36_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn accept_offer(o: u32) -> Weight {
+        (105_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(16 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(16 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn set_royalty() -> Weight {
+        (23_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn release_kitty(o: u32, c: u32) -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add((24_000_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(19 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(c as Weight)))
+    }
+    fn set_kitty_name() -> Weight {
+        (41_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_kitty_uri() -> Weight {
+        (29_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_marketplace_fee() -> Weight {
+        (14_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn create_collection() -> Weight {
+        (31_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn approve() -> Weight {
+        (22_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_approval_for_all() -> Weight {
+        (17_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn transfer_from(o: u32) -> Weight {
+        (44_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn batch_transfer(n: u32) -> Weight {
+        (21_000_000 as Weight)
+            .saturating_add((418_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads((30 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((52 as Weight).saturating_mul(n as Weight)))
+    }
+    fn batch_set_price(n: u32) -> Weight {
+        (19_000_000 as Weight)
+            .saturating_add((24_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads((6 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+    }
+    fn offer_transfer() -> Weight {
+        (33_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn accept_transfer(o: u32) -> Weight {
+        (47_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(14 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn reject_transfer() -> Weight {
+        (26_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn cancel_transfer() -> Weight {
+        (26_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_rental_terms() -> Weight {
+        (24_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn rent() -> Weight {
+        (62_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    fn fractionalize(o: u32) -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add((19_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn transfer_shares() -> Weight {
+        (28_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn redeem() -> Weight {
+        (46_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(8 as Weight))
+    }
+    fn buyout() -> Weight {
+        (84_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(13 as Weight))
+    }
+    fn claim_buyout() -> Weight {
+        (48_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn stake_kitty() -> Weight {
+        (27_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn unstake_kitty() -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn claim_rewards() -> Weight {
+        (56_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn fund_rewards() -> Weight {
+        (41_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn challenge() -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn cancel_challenge() -> Weight {
+        (31_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn reject_challenge() -> Weight {
+        (36_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn accept_challenge() -> Weight {
+        (64_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    fn on_initialize(n: u32) -> Weight {
+        (2_100_000 as Weight)
+            .saturating_add((450_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads((56 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((56 as Weight).saturating_mul(n as Weight)))
+    }
+}
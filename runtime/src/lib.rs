@@ -35,7 +35,7 @@ pub use frame_support::{
         constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
         IdentityFee, Weight,
     },
-    StorageValue,
+    PalletId, StorageValue,
 };
 pub use pallet_balances::Call as BalancesCall;
 use pallet_randomness_collective_flip;
@@ -103,7 +103,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     //   `spec_version`, and `authoring_version` are the same between Wasm and native.
     // This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
     //   the compatible custom types.
-    spec_version: 101,
+    spec_version: 102,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
     transaction_version: 1,
@@ -280,17 +280,77 @@ impl pallet_template::Config for Runtime {
     type Event = Event;
 }
 
+parameter_types! {
+    pub const KittyMutationProbability: Perbill = Perbill::from_percent(2);
+    pub const BaseBreedingCooldown: BlockNumber = 10 * MINUTES;
+    pub const MaxBreedingCooldown: BlockNumber = 7 * DAYS;
+    pub const InbreedingDepth: u32 = 2;
+    pub const AuctionExtension: BlockNumber = 10 * MINUTES;
+    pub const MaxAuctionDuration: BlockNumber = 30 * DAYS;
+    pub const MaxKittiesPerAccount: u32 = 1_000;
+    pub const MaxTotalKitties: Option<u32> = None;
+    pub const KittyDeposit: Balance = 10_000;
+    pub const KittyListingDeposit: Balance = 1_000;
+    pub const MaxKittyNameLength: u32 = 32;
+    pub const KittyNameDeposit: Balance = 1_000;
+    pub const MaxKittyRoyalty: Perbill = Perbill::from_percent(10);
+    pub const KittyMarketplaceFee: Perbill = Perbill::from_percent(2);
+    pub const KittiesPalletId: PalletId = PalletId(*b"py/kitty");
+    pub const MaxKittyOffers: u32 = 20;
+    pub const MaxKittyOfferDuration: BlockNumber = 30 * DAYS;
+    pub const MaxKittyBatchSize: u32 = 100;
+    pub const KittyTransferOfferTimeout: BlockNumber = 3 * DAYS;
+    pub const KittyXpPerBlock: u64 = 1;
+    pub const KittyStakingRewardPerBlock: Balance = 10;
+    pub const KittyBattleCooldown: BlockNumber = HOURS;
+    pub const MinKittyChallengeStake: Balance = 1_000;
+    pub const KittyChallengeTimeout: BlockNumber = 3 * DAYS;
+    pub const MaxKittyChallenges: u32 = 10;
+    pub const MaxKittyBattleHistory: u32 = 20;
+    pub const MaxKittyScheduledPerBlock: u32 = 50;
+}
+
 impl pallet_kitties::Config for Runtime {
     type Event = Event;
     type Randomness = RandomnessCollectiveFlip;
     // type KittyIndex = u32;
     type Currency = Balances;
+    type Breeder = pallet_kitties::genetics::MendelianBreeder<KittyMutationProbability>;
+    type BaseBreedingCooldown = BaseBreedingCooldown;
+    type MaxBreedingCooldown = MaxBreedingCooldown;
+    type InbreedingDepth = InbreedingDepth;
+    type AuctionExtension = AuctionExtension;
+    type MaxAuctionDuration = MaxAuctionDuration;
+    type MaxKittiesPerAccount = MaxKittiesPerAccount;
+    type CollectionOrigin = frame_system::EnsureRoot<AccountId>;
+    type MaxTotalKitties = MaxTotalKitties;
+    type KittyDeposit = KittyDeposit;
+    type ListingDeposit = KittyListingDeposit;
+    type MaxNameLength = MaxKittyNameLength;
+    type NameDeposit = KittyNameDeposit;
+    type MaxRoyalty = MaxKittyRoyalty;
+    type MarketplaceFee = KittyMarketplaceFee;
+    type PalletId = KittiesPalletId;
+    type OnMarketplaceFee = pallet_kitties::FeeAccount<Runtime>;
+    type MaxOffers = MaxKittyOffers;
+    type MaxOfferDuration = MaxKittyOfferDuration;
+    type MaxBatchSize = MaxKittyBatchSize;
+    type TransferOfferTimeout = KittyTransferOfferTimeout;
+    type XpPerBlock = KittyXpPerBlock;
+    type RewardPerBlock = KittyStakingRewardPerBlock;
+    type BattleCooldown = KittyBattleCooldown;
+    type MinChallengeStake = MinKittyChallengeStake;
+    type ChallengeTimeout = KittyChallengeTimeout;
+    type MaxChallenges = MaxKittyChallenges;
+    type MaxBattleHistory = MaxKittyBattleHistory;
+    type MaxScheduledPerBlock = MaxKittyScheduledPerBlock;
+    type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
 }
 
 // configure NFT pallet
 parameter_types! {
-    pub const MaxClassMetadata: u32 = 0;
-    pub const MaxTokenMetadata: u32 = 0;
+    pub const MaxClassMetadata: u32 = 256;
+    pub const MaxTokenMetadata: u32 = 256;
 }
 
 impl orml_nft::Config for Runtime {
@@ -320,7 +380,7 @@ construct_runtime!(
         Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>},
         // Include the custom logic from the pallet-template in the runtime.
         TemplateModule: pallet_template::{Pallet, Call, Storage, Event<T>},
-        Kitties: pallet_kitties::{Pallet, Storage, Event<T>},
+        Kitties: pallet_kitties::{Pallet, Call, Config, Storage, Event<T>},
         Nft: orml_nft::{Pallet, Storage, Config<T>},
     }
 );
@@ -495,6 +555,7 @@ impl_runtime_apis! {
             list_benchmark!(list, extra, pallet_balances, Balances);
             list_benchmark!(list, extra, pallet_timestamp, Timestamp);
             list_benchmark!(list, extra, pallet_template, TemplateModule);
+            list_benchmark!(list, extra, pallet_kitties, Kitties);
 
             let storage_info = AllPalletsWithSystem::storage_info();
 
@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+# Regenerate the weights of pallet_kitties from the benchmarks in pallets/kitties/src/benchmarking.rs
+set -e
+
+echo "*** Building the node with benchmarks enabled"
+cargo build --release --features runtime-benchmarks
+
+echo "*** Benchmarking pallet_kitties"
+./target/release/node-template benchmark \
+    --chain=dev \
+    --execution=wasm \
+    --wasm-execution=compiled \
+    --pallet=pallet_kitties \
+    --extrinsic='*' \
+    --steps=50 \
+    --repeat=20 \
+    --heap-pages=4096 \
+    --template=./.maintain/frame-weight-template.hbs \
+    --output=./pallets/kitties/src/weights.rs